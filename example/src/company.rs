@@ -11,3 +11,211 @@ pub struct Company {
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use cypher_dto::{Cursor, Error, FieldSet, NodeEntity, Page, Paged, StampMode};
+    use neo4rs::{BoltInteger, BoltList, BoltMap, BoltNode, BoltType, Row};
+
+    /// Labels and names that need escaping.
+    #[derive(Clone, Debug, PartialEq, Node)]
+    #[labels("Postal Address", "Match")]
+    struct Address {
+        id: String,
+        #[name = "zip-code"]
+        zip_code: String,
+    }
+
+    #[test]
+    fn escaped_names() {
+        assert_eq!(
+            Address::as_query_obj(),
+            "`Postal Address`:`Match` { id: $id, `zip-code`: $`zip-code` }"
+        );
+        assert_eq!(
+            Address::to_query_obj(Some("n"), StampMode::Read),
+            "`Postal Address`:`Match` { id: $n_id, `zip-code`: $`n_zip-code` }"
+        );
+        let q = Address::find_query(&Address::filter().zip_code.eq("12345".to_owned()));
+        assert_eq!(
+            q.text,
+            "MATCH (n:`Postal Address`:`Match`) WHERE n.`zip-code` = $`n_zip-code` RETURN n"
+        );
+        assert!(q.has_param_key("n_zip-code"));
+    }
+
+    #[test]
+    fn prefixed_row() {
+        assert_eq!(
+            Company::return_fields("c", Some("acme")),
+            "c.name AS acme_name, c.state AS acme_state, c.created AS acme_created, c.updated AS acme_updated"
+        );
+        assert_eq!(
+            Address::return_fields("a", Some("a")),
+            "a.id AS a_id, a.`zip-code` AS `a_zip-code`"
+        );
+
+        let now = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let row = Row::new(
+            BoltList::from(vec![
+                BoltType::from("acme_name"),
+                BoltType::from("acme_state"),
+                BoltType::from("acme_created"),
+                BoltType::from("acme_updated"),
+            ]),
+            BoltList::from(vec![
+                BoltType::from("Acme"),
+                BoltType::from("CA"),
+                BoltType::from(now.fixed_offset()),
+                BoltType::from(now.fixed_offset()),
+            ]),
+        );
+        let acme = Company::try_from_row_prefixed(&row, Some("acme")).unwrap();
+        assert_eq!(acme.name, "Acme");
+        assert_eq!(acme.created, now);
+        let id = CompanyId::try_from_row_prefixed(&row, Some("acme")).unwrap();
+        assert_eq!(id, CompanyId::new("Acme", "CA"));
+        assert!(matches!(
+            Company::try_from_row_prefixed(&row, None),
+            Err(Error::MissingField(name)) if name == "name"
+        ));
+    }
+
+    #[test]
+    fn bolt_map() {
+        let acme = Company {
+            name: "Acme".to_owned(),
+            state: "CA".to_owned(),
+            created: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+            updated: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+        };
+        let map = acme.to_bolt_map(StampMode::Read);
+        assert_eq!(Company::try_from(map.clone()).unwrap(), acme);
+        assert_eq!(
+            CompanyId::try_from(map.clone()).unwrap(),
+            CompanyId {
+                name: "Acme".to_owned(),
+                state: "CA".to_owned(),
+            }
+        );
+
+        // Maps, nodes, and relationships can be decoded from a BoltType, e.g. the items of a `collect()`.
+        assert_eq!(Company::try_from(BoltType::Map(map.clone())).unwrap(), acme);
+        let node = BoltNode::new(
+            BoltInteger::new(1),
+            BoltList::from(vec![BoltType::from("Company")]),
+            map.clone(),
+        );
+        assert_eq!(Company::try_from(BoltType::Node(node)).unwrap(), acme);
+        // Nodes with other labels aren't decoded, even if their properties match.
+        let node = BoltNode::new(
+            BoltInteger::new(1),
+            BoltList::from(vec![BoltType::from("Person2")]),
+            map,
+        );
+        assert!(matches!(
+            Company::try_from(BoltType::Node(node)),
+            Err(Error::TypeMismatch(name)) if name == "Company"
+        ));
+
+        // The created timestamp isn't in the map when creating.
+        assert!(matches!(
+            Company::try_from(acme.to_bolt_map(StampMode::Create)),
+            Err(Error::MissingField(field)) if field == "created"
+        ));
+        assert!(matches!(
+            Company::try_from(BoltType::from(1)),
+            Err(Error::TypeMismatch(name)) if name == "Company"
+        ));
+        assert!(Company::try_from(BoltMap::new()).is_err());
+    }
+
+    #[test]
+    fn returning() {
+        let acme = Company {
+            name: "Acme".to_owned(),
+            state: "CA".to_owned(),
+            created: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+            updated: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+        };
+        let q = acme.create_returning_query();
+        assert_eq!(q.text, format!("{} RETURN n", acme.create_query().text));
+        assert_eq!(q.params, acme.create_query().params);
+        let q = acme.update_returning_query();
+        assert!(q.text.ends_with(" } RETURN n"));
+
+        let node = BoltNode::new(
+            BoltInteger::new(1),
+            BoltList::from(vec![BoltType::from("Company")]),
+            acme.to_bolt_map(StampMode::Read),
+        );
+        let row = Row::new(
+            BoltList::from(vec![BoltType::from("n")]),
+            BoltList::from(vec![BoltType::Node(node)]),
+        );
+        assert_eq!(Company::from_returned(&row).unwrap(), acme);
+        let row = Row::new(BoltList::new(), BoltList::new());
+        assert!(matches!(
+            Company::from_returned(&row),
+            Err(Error::MissingField(name)) if name == "n"
+        ));
+    }
+
+    #[test]
+    fn update_with_id() {
+        let acme = Company {
+            name: "Acme Corp".to_owned(),
+            state: "NV".to_owned(),
+            created: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+            updated: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+        };
+        let q = acme.update_with_id_query(&CompanyId::new("Acme", "CA"));
+        assert_eq!(
+            q.to_string(),
+            "MATCH (n:Company { name: $old_name, state: $old_state }) \
+             SET n += { name: $n_name, state: $n_state, updated: datetime() }\n\
+             // $n_name = 'Acme Corp'\n\
+             // $n_state = 'NV'\n\
+             // $old_name = 'Acme'\n\
+             // $old_state = 'CA'"
+        );
+    }
+
+    #[test]
+    fn paged() {
+        let acme = Company {
+            name: "Acme".to_owned(),
+            state: "CA".to_owned(),
+            created: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+            updated: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+        };
+        let q = Company::list(&Page::first(1).order_by(Company::fields().created.desc())).unwrap();
+        assert!(q.has_param_key("limit"));
+        // Only the listed type is bound.
+        assert!(matches!(
+            Company::list(&Page::first(1).order_by(crate::Person::fields().name.asc())),
+            Err(Error::InvalidQuery(_))
+        ));
+
+        // The query returns a single row with the items and a cursor.
+        let cursor = BoltType::from(vec![
+            BoltType::from(acme.created.fixed_offset()),
+            BoltType::from("Acme"),
+            BoltType::from("CA"),
+        ]);
+        let row = Row::new(
+            BoltList::from(vec![BoltType::from("items"), BoltType::from("cursor")]),
+            BoltList::from(vec![
+                BoltType::from(vec![BoltType::Map(acme.to_bolt_map(StampMode::Read))]),
+                cursor,
+            ]),
+        );
+        let page = Paged::<Company>::try_from(row).unwrap();
+        assert_eq!(page.items, vec![acme]);
+        assert_eq!(page.total, None);
+        let next = page.next.unwrap();
+        assert_eq!(next.to_string().parse::<Cursor>().unwrap(), next);
+    }
+}
@@ -15,14 +15,20 @@ pub struct Person {
     photo_url: Option<String>,
 }
 
+#[allow(dead_code)]
 #[derive(Relation)]
 struct Knows;
 
 #[cfg(test)]
 mod tests {
-    use cypher_dto::{FieldSet, NodeEntity, RelationBound, RelationEntity, StampMode};
+    use cypher_dto::{
+        FieldSet, FromBolt, NodeEntity, NodeId, Patch, Projection, RelationBound, RelationEntity,
+        RelationId, StampMode, Tracked,
+    };
+    use neo4rs::{BoltMap, BoltNull, BoltString, BoltType};
 
     use super::*;
+    use crate::CompanyId;
 
     #[test]
     fn person() {
@@ -78,10 +84,257 @@ mod tests {
                 .created_at(),
             Some(&now),
         );
+        let map = p.to_bolt_map(StampMode::Create);
+        assert_eq!(map.get::<String>("name2").unwrap(), "name2");
+        assert_eq!(map.get::<i64>("age").unwrap(), 42);
+        assert_eq!(map.get::<Vec<String>>("colors").unwrap(), ["red", "blue"]);
+        assert!(map.value.contains_key("photo_url"));
+        assert!(!map.value.contains_key("created_at"));
+        assert!(!map.value.contains_key("updated_at"));
+        assert!(p
+            .to_bolt_map(StampMode::Update)
+            .value
+            .contains_key("created_at"));
+        assert_eq!(p.to_bolt_map(StampMode::Read).value.len(), 7);
+
         let id: PersonId = p.identifier();
         let _ = Knows.create(
             RelationBound::Create(&p),
             RelationBound::Match::<Person>(&id),
         );
     }
+
+    #[derive(Projection)]
+    struct PersonView {
+        id: String,
+        #[name = "name2"]
+        name: String,
+        #[nested = "c"]
+        company: Option<CompanyId>,
+        #[nested = "f"]
+        friends: Vec<PersonId>,
+    }
+
+    fn map(entries: Vec<(&str, BoltType)>) -> BoltType {
+        BoltType::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (BoltString::from(k), v))
+                .collect::<BoltMap>(),
+        )
+    }
+
+    #[test]
+    fn filter() {
+        let filter = Person::filter();
+        let (clause, params) = filter
+            .name
+            .starts_with("A")
+            .and(filter.age.gt(30).or(filter.photo_url.is_null()))
+            .to_where_clause("p");
+        assert_eq!(
+            clause,
+            "WHERE (p.name2 STARTS WITH $p_name2 AND (p.age > $p_age OR p.photo_url IS NULL))"
+        );
+        assert_eq!(params["p_name2"], BoltType::from("A"));
+        assert_eq!(params["p_age"], BoltType::from(30));
+
+        let q = Person::find(&Person::filter().colors.eq(vec!["red".to_owned()]));
+        assert!(q.has_param_key("n_colors"));
+    }
+
+    #[test]
+    fn snapshots() {
+        let p = Person::new("a", "Alice", None, &["it's".to_owned()], None);
+        assert_eq!(
+            p.create_query().to_string(),
+            "CREATE (n:Person2:PersonExtraLabel { id: $id, name2: $name2, age: $age, colors: $colors, \
+             photo_url: $photo_url, created_at: datetime(), updated_at: datetime() })\n\
+             // $age = null\n\
+             // $colors = ['it\\'s']\n\
+             // $id = 'a'\n\
+             // $name2 = 'Alice'\n\
+             // $photo_url = null"
+        );
+        assert_eq!(
+            p.identifier().read_query().to_string(),
+            "MATCH (n:Person2:PersonExtraLabel { id: $id }) RETURN n\n// $id = 'a'"
+        );
+        let q = p.update_query();
+        assert_eq!(q.params.len(), 6);
+        assert!(q
+            .text
+            .starts_with("MATCH (n:Person2:PersonExtraLabel { id: $id }) SET n += {"));
+    }
+
+    #[test]
+    fn vars() {
+        let p = Person::new("a", "Alice", None, &[], None);
+        assert_eq!(
+            p.identifier().read_as_query("p").to_string(),
+            "MATCH (p:Person2:PersonExtraLabel { id: $p_id }) RETURN p\n// $p_id = 'a'"
+        );
+        assert_eq!(
+            p.identifier().read_projection_query::<PersonId>("p").text,
+            "MATCH (p:Person2:PersonExtraLabel { id: $p_id }) RETURN p{.id} AS p"
+        );
+        assert_eq!(
+            p.identifier().delete_as_query("p").text,
+            "MATCH (p:Person2:PersonExtraLabel { id: $p_id }) DETACH DELETE p"
+        );
+        let q = p.create_as_query("p");
+        assert!(q
+            .text
+            .starts_with("CREATE (p:Person2:PersonExtraLabel { id: $p_id, name2: $p_name2,"));
+        assert!(q.text.ends_with(") RETURN p"));
+        assert!(q.has_param_key("p_name2"));
+        let q = p.update_as_query("p");
+        assert!(q.text.starts_with(
+            "MATCH (p:Person2:PersonExtraLabel { id: $p_id }) SET p += { id: $p_id, name2: $p_name2,"
+        ));
+        assert!(q.text.ends_with(" } RETURN p"));
+        assert_eq!(q.params.len(), 6);
+    }
+
+    #[test]
+    fn patch() {
+        let id = PersonId { id: "a".to_owned() };
+        let patch = PersonPatch {
+            name: Some("Alice".to_owned()),
+            age: Patch::Set(30),
+            photo_url: Patch::Null,
+            ..Default::default()
+        };
+        assert_eq!(
+            patch.apply_query(&id).to_string(),
+            "MATCH (n:Person2:PersonExtraLabel { id: $id }) \
+             SET n += { age: $age, name2: $name2, photo_url: $photo_url, updated_at: datetime() }\n\
+             // $age = 30\n\
+             // $id = 'a'\n\
+             // $name2 = 'Alice'\n\
+             // $photo_url = null"
+        );
+        assert_eq!(
+            PersonPatch::default().apply_query(&id).text,
+            "MATCH (n:Person2:PersonExtraLabel { id: $id }) SET n += { updated_at: datetime() }"
+        );
+        assert_eq!(Patch::from(Some(1)), Patch::Set(1));
+        assert!(Patch::<u8>::from(None) == Patch::Null && Patch::<u8>::default().is_keep());
+    }
+
+    #[test]
+    fn tracked() {
+        let p = Tracked::new(Person::new("a", "Alice", None, &[], None));
+        assert!(p.build_changes().is_none());
+        let p = p.map(|p| p.into_builder().name("Alice").build());
+        assert!(!p.is_changed());
+
+        let mut p = p.map(|p| p.into_builder().age(Some(30)).build());
+        assert_eq!(
+            p.build_changes_query().unwrap().to_string(),
+            "MATCH (n:Person2:PersonExtraLabel { id: $id }) SET n += { age: $age, updated_at: datetime() }\n\
+             // $age = 30\n\
+             // $id = 'a'"
+        );
+
+        // Changing the id renames the node.
+        p.set(p.clone().into_builder().id("b").build());
+        assert_eq!(
+            p.build_changes_query().unwrap().to_string(),
+            "MATCH (n:Person2:PersonExtraLabel { id: $old_id }) \
+             SET n += { age: $n_age, id: $n_id, updated_at: datetime() }\n\
+             // $n_age = 30\n\
+             // $n_id = 'b'\n\
+             // $old_id = 'a'"
+        );
+        p.reset();
+        assert!(p.build_changes().is_none());
+        assert_eq!(p.original_id().id, "b");
+        assert_eq!(p.into_inner().age(), Some(30));
+    }
+
+    #[test]
+    fn aggregates() {
+        let q = Person::count_query(Some(&Person::filter().age.gte(18)));
+        assert_eq!(
+            q.text,
+            "MATCH (n:Person2:PersonExtraLabel) WHERE n.age >= $n_age RETURN count(n) AS count"
+        );
+        assert!(q.has_param_key("n_age"));
+        assert_eq!(
+            Person::count_query(None).text,
+            "MATCH (n:Person2:PersonExtraLabel) RETURN count(n) AS count"
+        );
+        assert!(PersonId { id: "a".to_owned() }.exists().has_param_key("id"));
+        assert!(KnowsId::count_from(&PersonId { id: "a".to_owned() }).has_param_key("n_id"));
+
+        let q = Person::group_by(Person::fields().age, None).unwrap();
+        assert_eq!(
+            q.text(),
+            "MATCH (n:Person2:PersonExtraLabel) WITH n.age AS value, count(n) AS count \
+             ORDER BY count DESC RETURN collect([value, count]) AS groups"
+        );
+        let row = neo4rs::Row::new(
+            vec![BoltType::from("groups")].into(),
+            vec![BoltType::from(vec![
+                BoltType::from(vec![BoltType::from(30), BoltType::from(2)]),
+                BoltType::from(vec![BoltType::Null(BoltNull), BoltType::from(1)]),
+            ])]
+            .into(),
+        );
+        assert_eq!(q.decode(&row).unwrap(), [(Some(30), 2), (None, 1)]);
+
+        let q =
+            Person::group_by(Person::fields().age, Some(&Person::filter().age.gte(18))).unwrap();
+        assert!(q.text().starts_with(
+            "MATCH (n:Person2:PersonExtraLabel) WHERE n.age >= $n_age WITH n.age AS value"
+        ));
+    }
+
+    #[test]
+    fn projection() {
+        assert_eq!(
+            Person::projection("p"),
+            "p{.id, .name2, .age, .colors, .photo_url, .created_at, .updated_at}"
+        );
+        assert_eq!(
+            PersonView::projection("p"),
+            "p{.id, .name2, company: c{.name, .state}, friends: collect(f{.id})}"
+        );
+
+        let value = map(vec![
+            ("id", BoltType::from("alice")),
+            ("name2", BoltType::from("Alice")),
+            (
+                "company",
+                map(vec![
+                    ("name", BoltType::from("Acme")),
+                    ("state", BoltType::from("CA")),
+                ]),
+            ),
+            (
+                "friends",
+                BoltType::from(vec![
+                    map(vec![("id", BoltType::from("bob"))]),
+                    map(vec![("id", BoltType::from("carol"))]),
+                ]),
+            ),
+        ]);
+        let view = PersonView::from_bolt(value).unwrap();
+        assert_eq!(view.id, "alice");
+        assert_eq!(view.name, "Alice");
+        assert_eq!(view.company, Some(CompanyId::new("Acme", "CA")));
+        assert_eq!(view.friends, [PersonId::new("bob"), PersonId::new("carol")]);
+
+        // From an OPTIONAL MATCH with no matches.
+        let value = map(vec![
+            ("id", BoltType::from("alice")),
+            ("name2", BoltType::from("Alice")),
+            ("company", BoltType::Null(BoltNull)),
+            ("friends", BoltType::from(Vec::<BoltType>::new())),
+        ]);
+        let view = PersonView::from_bolt(value).unwrap();
+        assert!(view.company.is_none());
+        assert!(view.friends.is_empty());
+    }
 }
@@ -14,7 +14,17 @@ pub struct WorkedAt {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cypher_dto::{FieldSet, StampMode};
+    use crate::{Company, CompanyId, Person, PersonId, WorkedAtId};
+    use chrono::TimeZone;
+    use cypher_dto::{
+        Bound, CypherRow, Direction, Error, FieldSet, FromBolt, Match, NodeId, PathBuilder,
+        RelationBound, RelationEntity, RelationId, StampMode, Statement, TypedPath, Var,
+    };
+    use neo4rs::{
+        BoltInteger, BoltList, BoltMap, BoltNode, BoltPath, BoltRelation, BoltString, BoltType,
+        BoltUnboundedRelation, Node, Row,
+    };
+    use std::num::NonZeroUsize;
 
     #[test]
     fn rename() {
@@ -23,4 +33,480 @@ mod tests {
             "foo: $foo"
         );
     }
+
+    fn company(name: &str) -> BoltType {
+        let properties: BoltMap = [
+            (BoltString::from("name"), BoltType::from(name)),
+            (BoltString::from("state"), BoltType::from("CA")),
+        ]
+        .into_iter()
+        .collect();
+        BoltType::Node(BoltNode::new(
+            BoltInteger::new(0),
+            BoltList::from(vec![BoltType::from(Company::typename())]),
+            properties,
+        ))
+    }
+
+    #[test]
+    fn from_bolt_type() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            WorkedAt::try_from(worked_at(until)).unwrap(),
+            WorkedAt { until }
+        );
+        // Relationships of another type aren't decoded, even if their properties match.
+        let BoltType::Relation(mut rel) = worked_at(until) else {
+            unreachable!()
+        };
+        rel.typ = BoltString::from("WORKS_AT");
+        assert!(matches!(
+            WorkedAtId::try_from(BoltType::Relation(rel)),
+            Err(Error::TypeMismatch(name)) if name == "WORKED_AT"
+        ));
+    }
+
+    fn worked_at(until: DateTime<Utc>) -> BoltType {
+        BoltType::Relation(BoltRelation {
+            id: BoltInteger::new(1),
+            start_node_id: BoltInteger::new(0),
+            end_node_id: BoltInteger::new(2),
+            typ: BoltString::from(WorkedAt::typename()),
+            properties: [(
+                BoltString::from("foo"),
+                BoltType::from(until.fixed_offset()),
+            )]
+            .into_iter()
+            .collect(),
+        })
+    }
+
+    #[test]
+    fn bound() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let row = Row::new(
+            BoltList::from(vec![
+                BoltType::from("s"),
+                BoltType::from("r"),
+                BoltType::from("e"),
+            ]),
+            BoltList::from(vec![company("Acme"), worked_at(until), company("Initech")]),
+        );
+        let (start, worked_at, end): (CompanyId, WorkedAt, CompanyId) =
+            Bound::try_from(row).unwrap().into();
+        assert_eq!(start, CompanyId::new("Acme", "CA"));
+        assert_eq!(worked_at, WorkedAt { until });
+        assert_eq!(end, CompanyId::new("Initech", "CA"));
+
+        let row = Row::new(BoltList::new(), BoltList::new());
+        assert!(Bound::<CompanyId, WorkedAt, CompanyId>::try_from(row).is_err());
+    }
+
+    #[test]
+    fn vars() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let w = WorkedAt { until };
+        let acme = CompanyId::new("Acme", "CA");
+        let q = w.create_as_query(
+            RelationBound::<Company>::Match(&acme),
+            RelationBound::<Company>::Match(&acme),
+            ("a", "w", "b"),
+        );
+        assert!(q
+            .text
+            .contains("CREATE (a)-[w:WORKED_AT { foo: $w_foo }]->(b)"));
+        assert!(q.text.contains("RETURN a, w, b"));
+        assert!(q.has_param_key("a_name") && q.has_param_key("b_name"));
+
+        let id = w.identifier();
+        assert_eq!(
+            id.read_as_query("w").text,
+            "MATCH ()-[w:WORKED_AT { foo: $w_foo }]->() RETURN w"
+        );
+        assert_eq!(
+            id.read_projection_query::<WorkedAtId>("w").text,
+            "MATCH ()-[w:WORKED_AT { foo: $w_foo }]->() RETURN w{.foo} AS w"
+        );
+        let q = id.read_bound_as_query::<Company, Company>(("a", "w", "b"));
+        assert!(q.text.contains("RETURN a, w, b"));
+        assert!(q.has_param_key("w_foo"));
+
+        let row = Row::new(
+            BoltList::from(vec![
+                BoltType::from("a"),
+                BoltType::from("w"),
+                BoltType::from("b"),
+            ]),
+            BoltList::from(vec![company("Acme"), worked_at(until), company("Initech")]),
+        );
+        let bound = Bound::<CompanyId, WorkedAt, CompanyId>::from_row_as(&row, ("a", "w", "b"));
+        assert_eq!(bound.unwrap().relation, w);
+        assert!(
+            Bound::<CompanyId, WorkedAt, CompanyId>::from_row_as(&row, ("s", "r", "e")).is_err()
+        );
+    }
+
+    #[test]
+    fn update_with_id() {
+        let old = WorkedAtId {
+            until: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+        };
+        let w = WorkedAt {
+            until: Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap(),
+        };
+        let q = w.update_with_id_query(&old);
+        assert_eq!(
+            q.text,
+            "MATCH ()-[r:WORKED_AT { foo: $old_foo }]->() SET r += { foo: $r_foo }"
+        );
+        assert!(q.has_param_key("old_foo") && q.has_param_key("r_foo"));
+    }
+
+    #[test]
+    fn returning() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let w = WorkedAt { until };
+        let acme = CompanyId::new("Acme", "CA");
+        let q = w.create_returning_query(
+            RelationBound::<Company>::Match(&acme),
+            RelationBound::<Company>::Match(&acme),
+        );
+        assert!(q
+            .text
+            .contains("CREATE (s)-[r:WORKED_AT { foo: $foo }]->(e)"));
+        assert!(q.text.ends_with(" RETURN s, r, e"));
+
+        let row = Row::new(
+            BoltList::from(vec![
+                BoltType::from("s"),
+                BoltType::from("r"),
+                BoltType::from("e"),
+            ]),
+            BoltList::from(vec![company("Acme"), worked_at(until), company("Initech")]),
+        );
+        let bound = WorkedAt::from_returned::<CompanyId, CompanyId>(&row).unwrap();
+        assert_eq!(bound.relation, w);
+        assert_eq!(bound.end, CompanyId::new("Initech", "CA"));
+    }
+
+    #[derive(CypherRow)]
+    struct Out {
+        #[column = "s"]
+        start: CompanyId,
+        w: WorkedAt,
+        total: i64,
+        companies: Vec<CompanyId>,
+        note: Option<String>,
+    }
+
+    #[derive(CypherRow)]
+    struct Grouped<K> {
+        key: K,
+        average: f64,
+    }
+
+    #[test]
+    fn cypher_row() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let row = Row::new(
+            BoltList::from(vec![
+                BoltType::from("s"),
+                BoltType::from("w"),
+                BoltType::from("total"),
+                BoltType::from("companies"),
+            ]),
+            BoltList::from(vec![
+                company("Acme"),
+                worked_at(until),
+                BoltType::from(2),
+                BoltType::from(vec![company("Acme"), company("Initech")]),
+            ]),
+        );
+        let out = Out::try_from(row).unwrap();
+        assert_eq!(out.start, CompanyId::new("Acme", "CA"));
+        assert_eq!(out.w, WorkedAt { until });
+        assert_eq!(out.total, 2);
+        assert_eq!(
+            out.companies,
+            [
+                CompanyId::new("Acme", "CA"),
+                CompanyId::new("Initech", "CA")
+            ]
+        );
+        assert_eq!(out.note, None);
+
+        let row = Row::new(
+            BoltList::from(vec![BoltType::from("s")]),
+            BoltList::from(vec![company("Acme")]),
+        );
+        assert!(matches!(
+            Out::try_from(row),
+            Err(Error::MissingField(name)) if name == "w"
+        ));
+
+        // Generic rows, and integers decoded as floats, e.g. from `sum()`.
+        let row = Row::new(
+            BoltList::from(vec![BoltType::from("key"), BoltType::from("average")]),
+            BoltList::from(vec![company("Acme"), BoltType::from(3)]),
+        );
+        let grouped = Grouped::<CompanyId>::try_from(row).unwrap();
+        assert_eq!(grouped.key, CompanyId::new("Acme", "CA"));
+        assert_eq!(grouped.average, 3.0);
+    }
+
+    #[test]
+    fn typed_query() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let fields = Company::fields();
+        let query = Match::node::<Company>("s")
+            .rel::<WorkedAt>()
+            .node::<Company>("e")
+            .where_(fields.state.clone().eq("CA".to_owned()))
+            .where_(WorkedAt::fields().until.lt(until))
+            .order_by(fields.name.clone().of("e").asc())
+            .limit(10)
+            .return_((
+                Var::<CompanyId>::new("s"),
+                Var::<WorkedAt>::new("r"),
+                fields.name.of("e"),
+            ))
+            .unwrap();
+        assert_eq!(
+            query.text(),
+            "MATCH (s:Company)-[r:WORKED_AT]->(e:Company) \
+             WHERE s.state = $s_state AND r.foo < $r_foo \
+             RETURN s, r, e.name AS e_name ORDER BY e.name LIMIT $limit"
+        );
+        assert_eq!(
+            query.params()["r_foo"],
+            BoltType::from(until.fixed_offset())
+        );
+
+        let row = Row::new(
+            BoltList::from(vec![
+                BoltType::from("s"),
+                BoltType::from("r"),
+                BoltType::from("e_name"),
+            ]),
+            BoltList::from(vec![
+                company("Acme"),
+                worked_at(until),
+                BoltType::from("Initech"),
+            ]),
+        );
+        let (start, worked_at, end) = query.decode(&row).unwrap();
+        assert_eq!(start, CompanyId::new("Acme", "CA"));
+        assert_eq!(worked_at, WorkedAt { until });
+        assert_eq!(end, "Initech");
+    }
+
+    #[test]
+    fn related() {
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let acme = CompanyId::new("Acme", "CA");
+        let query = acme
+            .related::<WorkedAt, Company>(Direction::Incoming)
+            .filter(WorkedAt::filter().until.gte(until))
+            .order_by(WorkedAt::fields().until.desc())
+            .order_by(Company::fields().name.asc())
+            .build()
+            .unwrap();
+        assert_eq!(
+            query.text(),
+            "MATCH (n:Company { name: $n_name, state: $n_state })<-[r:WORKED_AT]-(m:Company) \
+             WHERE r.foo >= $r_foo WITH r, m ORDER BY r.foo DESC, m.name \
+             RETURN collect([r, m]) AS related"
+        );
+        assert_eq!(
+            query.params().keys().collect::<Vec<_>>(),
+            ["n_name", "n_state", "r_foo"]
+        );
+
+        let initech = Company {
+            name: "Initech".to_owned(),
+            state: "CA".to_owned(),
+            created: until,
+            updated: until,
+        };
+        let node = BoltType::Node(BoltNode::new(
+            BoltInteger::new(2),
+            BoltList::from(vec![BoltType::from(Company::typename())]),
+            initech.to_bolt_map(StampMode::Read),
+        ));
+        let row = Row::new(
+            BoltList::from(vec![BoltType::from("related")]),
+            BoltList::from(vec![BoltType::from(vec![BoltType::from(vec![
+                worked_at(until),
+                node,
+            ])])]),
+        );
+        assert_eq!(query.decode(&row).unwrap(), [(WorkedAt { until }, initech)]);
+
+        let query = acme
+            .related_path::<WorkedAt, Company>(1..=3)
+            .filter(WorkedAt::filter().until.gte(until))
+            .build()
+            .unwrap();
+        assert_eq!(
+            query.text(),
+            "MATCH (n:Company { name: $n_name, state: $n_state })-[rs:WORKED_AT*1..3]->(m:Company) \
+             WHERE ALL(r IN rs WHERE r.foo >= $r_foo) WITH rs, m RETURN collect([rs, m]) AS related"
+        );
+
+        let query = acme
+            .related_path::<WorkedAt, Company>(1..=3)
+            .order_by(Company::fields().name.desc())
+            .build()
+            .unwrap();
+        assert!(query
+            .text()
+            .ends_with("WITH rs, m ORDER BY m.name DESC RETURN collect([rs, m]) AS related"));
+    }
+
+    #[test]
+    fn related_invalid_order() {
+        let acme = CompanyId::new("Acme", "CA");
+        let err = acme
+            .related_path::<WorkedAt, Company>(1..=3)
+            .order_by(WorkedAt::fields().until.asc())
+            .build()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Can't order a path by the relationship field WORKED_AT.foo"));
+
+        let err = acme
+            .related::<WorkedAt, Company>(Direction::Incoming)
+            .order_by(Person::fields().name.asc())
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidQuery(_)));
+    }
+
+    /// Any of the node types in a path.
+    enum AnyNode {
+        Person(Person),
+        Company(Company),
+    }
+    impl TryFrom<Node> for AnyNode {
+        type Error = Error;
+        fn try_from(node: Node) -> Result<Self, Error> {
+            if node.labels().iter().any(|l| *l == Company::typename()) {
+                Ok(AnyNode::Company(node.try_into()?))
+            } else {
+                Ok(AnyNode::Person(node.try_into()?))
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_path() {
+        let alice = PersonId::new("alice");
+        let q = alice.shortest_path_query::<WorkedAt, _>(
+            &CompanyId::new("Acme", "CA"),
+            NonZeroUsize::new(4).unwrap(),
+        );
+        assert!(q
+            .to_string()
+            .contains(" WHERE s <> e MATCH p = shortestPath((s)-[:WORKED_AT*..4]-(e)) RETURN p"));
+        assert!(q.has_param_key("s_id"));
+        assert!(q.has_param_key("e_state"));
+
+        // (alice)-[:WORKED_AT]->(acme)
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let acme = Company {
+            name: "Acme".to_owned(),
+            state: "CA".to_owned(),
+            created: until,
+            updated: until,
+        };
+        let person = Person::new("alice", "Alice", None, &[], None);
+        let node = |id, label: &str, props| {
+            BoltType::Node(BoltNode::new(
+                BoltInteger::new(id),
+                BoltList::from(vec![BoltType::from(label)]),
+                props,
+            ))
+        };
+        let path = BoltType::Path(BoltPath {
+            nodes: BoltList::from(vec![
+                node(0, "Person2", person.to_bolt_map(StampMode::Read)),
+                node(1, "Company", acme.to_bolt_map(StampMode::Read)),
+            ]),
+            rels: BoltList::from(vec![BoltType::UnboundedRelation(
+                BoltUnboundedRelation::new(
+                    BoltInteger::new(2),
+                    BoltString::from("WORKED_AT"),
+                    WorkedAt { until }.to_bolt_map(StampMode::Read),
+                ),
+            )]),
+            indices: BoltList::from(vec![BoltType::from(1), BoltType::from(1)]),
+        });
+        let path = TypedPath::<AnyNode, WorkedAt>::from_bolt(path).unwrap();
+        assert!(matches!(path.start(), Some(AnyNode::Person(p)) if p.name() == "Alice"));
+        assert!(matches!(path.end(), Some(AnyNode::Company(c)) if c == &acme));
+        assert_eq!(path.relations, [WorkedAt { until }]);
+    }
+
+    #[test]
+    fn path_builder() {
+        let alice = Person::new("alice", "Alice", None, &[], None);
+        let bob = Person::new("bob", "Bob", None, &[], None);
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let q = PathBuilder::start(RelationBound::Create(&alice))
+            .then(&WorkedAt { until }, RelationBound::Merge(&bob))
+            .then(
+                &crate::WorksAt {},
+                RelationBound::<Company>::Match(&CompanyId::new("Acme", "CA")),
+            )
+            .returning()
+            .build();
+        assert!(q.has_param_key("n0_id"));
+        assert!(q.has_param_key("r0_foo"));
+        assert!(q.has_param_key("n1_id"));
+        assert!(q.has_param_key("n2_name"));
+        assert!(q.has_param_key("n2_state"));
+        assert!(!q.has_param_key("id"));
+    }
+
+    #[test]
+    fn statement() {
+        let alice = Person::new("alice", "Alice", None, &[], None);
+        let until = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        let mut s = Statement::new();
+        let a = s.match_("a", &CompanyId::new("Acme", "CA"));
+        let name = s.param("a_name", "Acme");
+        let p = s.create("a", &alice);
+        let w = s.relate("w", &WorkedAt { until }, &p, &a);
+        s.push(format!("RETURN {}, {}, {}, ${}", a, p, w, name));
+        assert_eq!(
+            (a.as_str(), p.as_str(), name.as_str()),
+            ("a", "a2", "aname")
+        );
+        assert_eq!(
+            s.params().keys().collect::<Vec<_>>(),
+            [
+                "a2_age",
+                "a2_colors",
+                "a2_id",
+                "a2_name2",
+                "a2_photo_url",
+                "a_name",
+                "a_state",
+                "aname",
+                "w_foo"
+            ]
+        );
+        let q: neo4rs::Query = s.into();
+        assert!(q.has_param_key("w_foo"));
+
+        // The MATCH comes first, even when it's added after a write.
+        let mut s = Statement::new();
+        let p = s.create("p", &alice);
+        let c = s.match_("c", &CompanyId::new("Acme", "CA"));
+        s.relate("w", &WorkedAt { until }, &p, &c);
+        let text = s.text();
+        let clauses: Vec<&str> = text.lines().filter_map(|l| l.split(' ').next()).collect();
+        assert_eq!(clauses, ["MATCH", "CREATE", "CREATE"]);
+    }
 }
@@ -3,3 +3,47 @@ use cypher_dto::Relation;
 /// A fieldless relation.
 #[derive(Clone, Debug, PartialEq, Relation)]
 pub struct WorksAt {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CompanyId;
+    use cypher_dto::{Page, RelationId};
+    use neo4rs::BoltType;
+
+    #[test]
+    fn list_from() {
+        let acme = CompanyId::new("Acme", "CA");
+        let q = WorksAtId::list_from_query(&acme, &Page::first(10)).unwrap();
+        assert!(q.text.contains("WITH r ORDER BY elementId(r) LIMIT $limit"));
+
+        // The next page has a key to start after, even without id fields.
+        let q = WorksAtId::list_from_query(
+            &acme,
+            &Page::first(10).after(cursor_from(vec![BoltType::from("4:abc:1")])),
+        )
+        .unwrap();
+        assert!(q.text.contains("WHERE (elementId(r) > $cursor) WITH r"));
+    }
+
+    #[test]
+    fn patch() {
+        // There's no `apply()`, since a relation without id fields can't be matched.
+        assert!(WorksAtPatch::default().to_bolt_map().value.is_empty());
+    }
+
+    fn cursor_from(values: Vec<BoltType>) -> cypher_dto::Cursor {
+        let row = neo4rs::Row::new(
+            vec![BoltType::from("items"), BoltType::from("cursor")].into(),
+            vec![
+                BoltType::from(Vec::<BoltType>::new()),
+                BoltType::from(values),
+            ]
+            .into(),
+        );
+        cypher_dto::Paged::<WorksAt>::try_from(row)
+            .unwrap()
+            .next
+            .unwrap()
+    }
+}
@@ -1,9 +1,10 @@
 mod common;
 
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use common::*;
 use cypher_dto::*;
 use neo4rs::{Node, Query};
+use std::num::NonZeroUsize;
 
 #[tokio::test]
 async fn basic_crud() {
@@ -66,3 +67,261 @@ async fn basic_crud() {
     let row = stream.next().await.unwrap();
     assert!(row.is_none());
 }
+
+#[tokio::test]
+async fn find() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let group = uuid();
+    let people: Vec<Person> = [("Alice", Some(30)), ("Allison", None), ("Bob", Some(40))]
+        .into_iter()
+        .map(|(name, age)| Person::new(&uuid(), name, age, std::slice::from_ref(&group), None))
+        .collect();
+    graph.run(Person::create_many(&people, None)).await.unwrap();
+
+    let filter = Person::filter();
+    let query = Person::find(
+        &filter
+            .colors
+            .eq(vec![group.clone()])
+            .and(filter.name.starts_with("Al"))
+            .and(filter.age.lt(35).or(filter.age.is_null())),
+    );
+    let mut stream = graph.execute(query).await.unwrap();
+    let mut names = Vec::new();
+    while let Some(row) = stream.next().await.unwrap() {
+        names.push(
+            Person::try_from(row.get::<Node>("n").unwrap())
+                .unwrap()
+                .name()
+                .to_owned(),
+        );
+    }
+    names.sort();
+    assert_eq!(names, ["Alice", "Allison"]);
+}
+
+#[tokio::test]
+async fn paging() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    graph.run(acme.create()).await.unwrap();
+    let mut people = Vec::new();
+    for i in 0..5 {
+        let person = Person::new(&uuid(), &format!("Person {i}"), Some(i), &[], None);
+        graph
+            .run(WorksAt {}.create(
+                RelationBound::Create(&person),
+                RelationBound::<Company>::Match(&acme.identifier()),
+            ))
+            .await
+            .unwrap();
+        people.push(person);
+    }
+
+    // Oldest first, two at a time, following the cursor.
+    let mut page = Page::first(2)
+        .order_by(Person::fields().age.desc())
+        .with_total();
+    let mut names = Vec::new();
+    loop {
+        let mut stream = graph.execute(Person::list(&page).unwrap()).await.unwrap();
+        let paged = Paged::<Person>::try_from(stream.next().await.unwrap().unwrap()).unwrap();
+        assert_eq!(paged.total, Some(5));
+        names.extend(paged.items.iter().map(|p| p.name().to_owned()));
+        match paged.next {
+            Some(cursor) => page = page.after(cursor.to_string().parse().unwrap()),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = people.iter().rev().map(|p| p.name().to_owned()).collect();
+    assert_eq!(names, expected);
+
+    // Relationships from a node.
+    let mut stream = graph
+        .execute(
+            WorksAtId::list_from(&people[0].identifier(), &Page::default().with_total()).unwrap(),
+        )
+        .await
+        .unwrap();
+    let paged = Paged::<WorksAt>::try_from(stream.next().await.unwrap().unwrap()).unwrap();
+    assert_eq!(paged.items, vec![WorksAt {}]);
+    assert_eq!(paged.total, Some(1));
+    assert_eq!(paged.next, None);
+}
+
+#[tokio::test]
+async fn aggregates() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    let ages = [Some(30), Some(30), None];
+    for age in ages {
+        let person = Person::new(&uuid(), "Alice", age, &[], None);
+        graph
+            .run(WorksAt {}.create(RelationBound::Create(&person), RelationBound::Merge(&acme)))
+            .await
+            .unwrap();
+    }
+
+    let count = |q: Query| async {
+        let mut stream = graph.execute(q).await.unwrap();
+        stream
+            .next()
+            .await
+            .unwrap()
+            .unwrap()
+            .get::<i64>("count")
+            .unwrap()
+    };
+    assert_eq!(count(Person::count(None)).await, 3);
+    assert_eq!(
+        count(Person::count(Some(&Person::filter().age.is_null()))).await,
+        1
+    );
+    assert_eq!(
+        count(WorksAtId::count_from_directed(
+            &acme.identifier(),
+            Direction::Incoming
+        ))
+        .await,
+        3
+    );
+
+    let mut stream = graph.execute(acme.identifier().exists()).await.unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert!(row.get::<bool>("exists").unwrap());
+    let mut stream = graph
+        .execute(PersonId::new(&uuid()).exists())
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert!(!row.get::<bool>("exists").unwrap());
+
+    let groups = Person::group_by(Person::fields().age, None).unwrap();
+    let mut stream = graph.execute(groups.query()).await.unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert_eq!(groups.decode(&row).unwrap(), [(Some(30), 2), (None, 1)]);
+}
+
+#[tokio::test]
+async fn related() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let bob = Person::new(&uuid(), "Bob", None, &[], None);
+    graph.run(bob.create()).await.unwrap();
+    let mut companies = Vec::new();
+    for (i, year) in [2019, 2021, 2023].into_iter().enumerate() {
+        let company = Company {
+            name: format!("Company {i}"),
+            state: uuid(),
+            created: Utc::now(),
+            updated: Utc::now(),
+        };
+        let until = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+        graph
+            .run(WorkedAt { until }.create(
+                RelationBound::<Person>::Match(&bob.identifier()),
+                RelationBound::Create(&company),
+            ))
+            .await
+            .unwrap();
+        companies.push(company);
+    }
+
+    let since = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    let query = bob
+        .identifier()
+        .related::<WorkedAt, Company>(Direction::Outgoing)
+        .filter(WorkedAt::filter().until.gte(since))
+        .order_by(WorkedAt::fields().until.desc())
+        .build()
+        .unwrap();
+    let mut stream = graph.execute(query.query()).await.unwrap();
+    let related = query
+        .decode(&stream.next().await.unwrap().unwrap())
+        .unwrap();
+    let names: Vec<&str> = related.iter().map(|(_, c)| c.name.as_str()).collect();
+    assert_eq!(names, ["Company 2", "Company 1"]);
+
+    let query = bob
+        .identifier()
+        .related_path::<WorkedAt, Company>(1..=2)
+        .build()
+        .unwrap();
+    let mut stream = graph.execute(query.query()).await.unwrap();
+    let paths = query
+        .decode(&stream.next().await.unwrap().unwrap())
+        .unwrap();
+    assert_eq!(paths.len(), 3);
+    assert!(paths.iter().all(|(rels, _)| rels.len() == 1));
+}
+
+#[tokio::test]
+async fn shortest_path() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    // (alice)-[:WORKS_AT]->(acme)<-[:WORKS_AT]-(bob)
+    let alice = Person::new(&uuid(), "Alice", None, &[], None);
+    let bob = Person::new(&uuid(), "Bob", None, &[], None);
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    for person in [&alice, &bob] {
+        graph
+            .run(WorksAt {}.create(RelationBound::Create(person), RelationBound::Merge(&acme)))
+            .await
+            .unwrap();
+    }
+
+    let mut stream = graph
+        .execute(
+            alice
+                .identifier()
+                .shortest_path::<WorksAt, _>(&bob.identifier(), NonZeroUsize::new(4).unwrap()),
+        )
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    let path = TypedPath::<Node, WorksAt>::try_from(row.get::<neo4rs::Path>("p").unwrap()).unwrap();
+    assert_eq!(path.len(), 2);
+    assert_eq!(
+        Person::try_from(path.nodes[0].clone()).unwrap().name(),
+        "Alice"
+    );
+    assert_eq!(Company::try_from(path.nodes[1].clone()).unwrap(), acme);
+    assert_eq!(
+        Person::try_from(path.nodes[2].clone()).unwrap().name(),
+        "Bob"
+    );
+
+    // There is no path with every relationship going outwards.
+    let mut stream = graph
+        .execute(alice.identifier().shortest_path_directed::<WorksAt, _>(
+            &bob.identifier(),
+            NonZeroUsize::new(4).unwrap(),
+            Direction::Outgoing,
+        ))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+}
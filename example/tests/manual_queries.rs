@@ -3,7 +3,7 @@ mod common;
 use chrono::{TimeZone, Utc};
 use common::*;
 use cypher_dto::*;
-use neo4rs::{Node, Query, Relation};
+use neo4rs::{BoltMap, BoltType, Node, Query, Relation};
 
 #[tokio::test]
 async fn create_all_at_once() {
@@ -75,6 +75,172 @@ async fn create_all_at_once() {
 
     let worked_at2_db: WorkedAt = row.get::<Relation>("w2").unwrap().try_into().unwrap();
     assert_eq!(worked_at2, worked_at2_db);
+
+    // Read a relation back together with the nodes it connects.
+    let mut stream = graph
+        .execute(worked_at.identifier().read_bound::<Person, Company>())
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+    let bound: Bound<Person, WorkedAt, Company> = row.try_into().unwrap();
+    assert_eq!(bound.start.id(), bob.id());
+    assert_eq!(bound.relation, worked_at);
+    assert_eq!(bound.end.identifier(), acme.identifier());
+
+    // Or just their ids.
+    let mut stream = graph
+        .execute(worked_at2.identifier().read_bound::<Person, Company>())
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    let (bob_id, w2, acme_id): (PersonId, WorkedAt, CompanyId) =
+        Bound::try_from(row).unwrap().into();
+    assert_eq!(bob_id, bob.identifier());
+    assert_eq!(w2, worked_at2);
+    assert_eq!(acme_id, acme.identifier());
+
+    // Decode a whole row at once.
+    #[derive(CypherRow)]
+    struct Out {
+        acme: Company,
+        total: i64,
+        #[column = "people"]
+        employees: Vec<Person>,
+        latest: Option<WorkedAt>,
+    }
+    let q = Query::new(format!(
+        "MATCH (p:{})-[w:{}]->(acme:{})
+         RETURN acme, count(w) AS total, collect(DISTINCT p) AS people, null AS latest",
+        Person::labels().join(":"),
+        WorkedAt::typename(),
+        CompanyId::as_query_obj(),
+    ));
+    let q = acme
+        .identifier()
+        .add_values_to_params(q, None, StampMode::Read);
+    let mut stream = graph.execute(q).await.unwrap();
+    let out: Out = stream.next().await.unwrap().unwrap().try_into().unwrap();
+    assert_eq!(out.acme.identifier(), acme.identifier());
+    assert_eq!(out.total, 2);
+    assert_eq!(out.employees.len(), 1);
+    assert_eq!(out.employees[0].id(), bob.id());
+    assert!(out.latest.is_none());
+
+    // Or as a nested map projection.
+    #[derive(Projection)]
+    struct CompanyView {
+        name: String,
+        #[nested = "p"]
+        employees: Vec<PersonId>,
+    }
+    let q = Query::new(format!(
+        "MATCH (p:{})-[:{}]->(acme:{})
+         RETURN {} AS acme",
+        Person::labels().join(":"),
+        WorkedAt::typename(),
+        CompanyId::as_query_obj(),
+        CompanyView::projection("acme"),
+    ));
+    let q = acme
+        .identifier()
+        .add_values_to_params(q, None, StampMode::Read);
+    let mut stream = graph.execute(q).await.unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    let view = CompanyView::from_column(&row, "acme").unwrap();
+    assert_eq!(view.name, acme.name);
+    assert_eq!(view.employees, vec![bob.identifier(), bob.identifier()]);
+}
+
+#[tokio::test]
+async fn create_path() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    graph.run(acme.create()).await.unwrap();
+
+    let alice = Person::new(&uuid(), "Alice", None, &[], None);
+    let bob = Person::new(&uuid(), "Bob", Some(42), &[], None);
+    let worked_at = WorkedAt {
+        until: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+    };
+    // (alice)-[:WORKED_AT]->(bob)-[:WORKS_AT]->(acme)
+    let q = PathBuilder::start(RelationBound::Create(&alice))
+        .then(&worked_at, RelationBound::Merge(&bob))
+        .then(
+            &WorksAt {},
+            RelationBound::<Company>::Match(&acme.identifier()),
+        )
+        .returning()
+        .build();
+    let mut stream = graph.execute(q).await.unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+
+    let alice_db: Person = row.get::<Node>("n0").unwrap().try_into().unwrap();
+    assert_eq!(alice_db.id(), alice.id());
+    let worked_at_db: WorkedAt = row.get::<Relation>("r0").unwrap().try_into().unwrap();
+    assert_eq!(worked_at_db, worked_at);
+    let bob_db: Person = row.get::<Node>("n1").unwrap().try_into().unwrap();
+    assert_eq!(bob_db.age(), Some(42));
+    let _: WorksAt = row.get::<Relation>("r1").unwrap().try_into().unwrap();
+    let acme_db: Company = row.get::<Node>("n2").unwrap().try_into().unwrap();
+    assert_eq!(acme_db.identifier(), acme.identifier());
+}
+
+#[tokio::test]
+async fn whole_dto_params() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+        updated: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap(),
+    };
+    let q = Query::new("CREATE (n:Company $props)".to_owned())
+        .param("props", BoltType::Map(acme.to_bolt_map(StampMode::Read)));
+    graph.run(q).await.unwrap();
+
+    let mut moved = acme.clone();
+    moved.updated = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+    let q = Query::new(format!(
+        "MATCH (n:{}) SET n += $props",
+        CompanyId::as_query_obj()
+    ))
+    .param("props", BoltType::Map(moved.to_bolt_map(StampMode::Read)));
+    let q = acme
+        .identifier()
+        .add_values_to_params(q, None, StampMode::Read);
+    graph.run(q).await.unwrap();
+
+    // Map projections and collected nodes.
+    let q = Query::new(format!(
+        "MATCH (n:{}) RETURN n{{.*}} AS props, collect(n) AS nodes",
+        CompanyId::as_query_obj()
+    ));
+    let q = acme
+        .identifier()
+        .add_values_to_params(q, None, StampMode::Read);
+    let mut stream = graph.execute(q).await.unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    let props = Company::try_from(row.get::<BoltMap>("props").unwrap()).unwrap();
+    assert_eq!(props, moved);
+    let nodes = row
+        .get::<Vec<BoltType>>("nodes")
+        .unwrap()
+        .into_iter()
+        .map(Company::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(nodes, vec![moved]);
 }
 
 // #[tokio::test]
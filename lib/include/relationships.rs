@@ -33,7 +33,7 @@
     query,
     "CREATE (s:Person { name: $s_name }) \
     CREATE (e:Person { name: $e_name }) \
-    CREATE (s)-[:KNOWS { since: $since }]->(e)"
+    CREATE (s)-[r:KNOWS { since: $since }]->(e)"
   );
 
   // Use it in a [neo4rs::Query]:
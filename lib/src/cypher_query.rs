@@ -0,0 +1,361 @@
+use crate::{format_param, FieldSet, StampMode};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use neo4rs::{BoltMap, BoltType, Query};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Write},
+};
+
+/// A query whose text and parameters can be read back, unlike a [Query], e.g. for snapshot tests.
+///
+/// Each query generator has a `_query` variant that returns one of these, e.g. `person.create_query()`.
+/// It converts into a [Query] with `.into()`.
+///
+/// [Display] renders the text, followed by a `// $name = value` comment for each parameter, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CypherQuery {
+    pub text: String,
+    pub params: BTreeMap<String, BoltType>,
+}
+impl CypherQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            params: BTreeMap::new(),
+        }
+    }
+    /// Adds a parameter, replacing any with the same name.
+    pub fn param(mut self, name: &str, value: impl Into<BoltType>) -> Self {
+        self.params.insert(name.to_owned(), value.into());
+        self
+    }
+    /// Adds the fields of a DTO as parameters, the same as [FieldSet::add_values_to_params].
+    pub fn add_values<T: FieldSet>(self, dto: &T, prefix: Option<&str>, mode: StampMode) -> Self {
+        self.add_map(dto.to_bolt_map(mode), prefix)
+    }
+    /// Adds each entry of a map as a parameter, with an optional prefix like [format_param].
+    pub fn add_map(mut self, map: BoltMap, prefix: Option<&str>) -> Self {
+        for (key, value) in map.value {
+            self.params.insert(format_param(&key.value, prefix), value);
+        }
+        self
+    }
+    pub fn has_param_key(&self, name: &str) -> bool {
+        self.params.contains_key(name)
+    }
+    /// Renders the query with each `$param` replaced by its value as a Cypher literal, e.g. to paste
+    /// into Neo4j Browser or `cypher-shell` while debugging.
+    ///
+    /// **Do not execute the result**: it's for people to read. Inlined values defeat the query plan cache,
+    /// and escaping is no substitute for parameters.
+    ///
+    /// Parameters named in `redact`, with or without a prefix (`password` also masks `$n_password`),
+    /// and map entries with those keys, are replaced with `'<redacted>'`. Unknown parameters are left as they are.
+    pub fn inline_params_unsafe(&self, redact: &[&str]) -> String {
+        let mut out = String::with_capacity(self.text.len());
+        let mut chars = self.text.char_indices().peekable();
+        let mut quote = None;
+        while let Some((i, c)) = chars.next() {
+            match (quote, c) {
+                // Inside a string or escaped name, only look for the end of it.
+                (Some(_), '\\') => {
+                    out.push(c);
+                    if let Some((_, next)) = chars.next() {
+                        out.push(next);
+                    }
+                    continue;
+                }
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"' | '`') => quote = Some(c),
+                (None, '$') => {
+                    let mut name = String::new();
+                    if let Some((_, '`')) = chars.peek() {
+                        // An escaped name, e.g. $`n_zip-code`, where "``" is a backtick.
+                        chars.next();
+                        while let Some((_, c)) = chars.next() {
+                            if c == '`' {
+                                if !matches!(chars.peek(), Some((_, '`'))) {
+                                    break;
+                                }
+                                chars.next();
+                            }
+                            name.push(c);
+                        }
+                    } else {
+                        while let Some((_, c)) = chars.peek() {
+                            if !(c.is_alphanumeric() || *c == '_') {
+                                break;
+                            }
+                            name.push(*c);
+                            chars.next();
+                        }
+                    }
+                    match self.params.get(name.as_str()) {
+                        Some(_) if is_redacted(&name, redact) => out.push_str(REDACTED),
+                        // Writing to a String can't fail.
+                        Some(value) => write_literal(&mut out, value, redact).unwrap(),
+                        None => {
+                            let end = chars.peek().map_or(self.text.len(), |(j, _)| *j);
+                            out.push_str(&self.text[i..end]);
+                        }
+                    }
+                    continue;
+                }
+                (None, _) => {}
+            }
+            out.push(c);
+        }
+        out
+    }
+    pub fn into_query(self) -> Query {
+        self.params
+            .into_iter()
+            .fold(Query::new(self.text), |q, (k, v)| q.param(&k, v))
+    }
+}
+impl From<CypherQuery> for Query {
+    fn from(query: CypherQuery) -> Self {
+        query.into_query()
+    }
+}
+impl Display for CypherQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)?;
+        for (name, value) in &self.params {
+            write!(f, "\n// ${} = ", name)?;
+            write_literal(f, value, &[])?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a value as a Cypher literal, e.g. `'it\'s'`, `[1, 2]` or `datetime('2021-01-01T00:00:00+00:00')`.
+///
+/// Map keys are sorted, so the output is deterministic. Map entries with a key in `redact` are masked.
+fn write_literal(f: &mut impl Write, value: &BoltType, redact: &[&str]) -> fmt::Result {
+    match value {
+        BoltType::Null(_) => f.write_str("null"),
+        BoltType::Boolean(b) => write!(f, "{}", b.value),
+        BoltType::Integer(i) => write!(f, "{}", i.value),
+        BoltType::Float(x) => write_float(f, x.value),
+        BoltType::String(s) => write_string(f, &s.value),
+        BoltType::Bytes(b) => write_list(f, b.value.iter(), |f, byte| write!(f, "{}", byte)),
+        BoltType::List(list) => {
+            write_list(f, list.value.iter(), |f, v| write_literal(f, v, redact))
+        }
+        BoltType::Map(map) => write_map(f, map, redact),
+        BoltType::Node(node) => write_map(f, &node.properties, redact),
+        BoltType::Relation(rel) => write_map(f, &rel.properties, redact),
+        BoltType::UnboundedRelation(rel) => write_map(f, &rel.properties, redact),
+        BoltType::Path(path) => write_list(f, path.nodes.value.iter(), |f, v| {
+            write_literal(f, v, redact)
+        }),
+        BoltType::Point2D(p) => write!(
+            f,
+            "point({{srid: {}, x: {:?}, y: {:?}}})",
+            p.sr_id.value, p.x.value, p.y.value
+        ),
+        BoltType::Point3D(p) => write!(
+            f,
+            "point({{srid: {}, x: {:?}, y: {:?}, z: {:?}}})",
+            p.sr_id.value, p.x.value, p.y.value, p.z.value
+        ),
+        BoltType::Duration(d) => {
+            let d: std::time::Duration = d.clone().into();
+            write!(
+                f,
+                "duration({{seconds: {}, nanoseconds: {}}})",
+                d.as_secs(),
+                d.subsec_nanos()
+            )
+        }
+        BoltType::Date(d) => match NaiveDate::try_from(d) {
+            Ok(d) => write!(f, "date('{}')", d),
+            Err(_) => f.write_str("null"),
+        },
+        BoltType::Time(t) => {
+            let (time, offset): (NaiveTime, FixedOffset) = t.into();
+            write!(f, "time('{}{}')", time, offset)
+        }
+        BoltType::LocalTime(t) => write!(f, "localtime('{}')", NaiveTime::from(t)),
+        BoltType::DateTime(dt) => match DateTime::<FixedOffset>::try_from(dt) {
+            Ok(dt) => write!(f, "datetime('{}')", dt.to_rfc3339()),
+            Err(_) => f.write_str("null"),
+        },
+        BoltType::LocalDateTime(dt) => match NaiveDateTime::try_from(dt) {
+            Ok(dt) => write!(f, "localdatetime('{}')", dt.format("%Y-%m-%dT%H:%M:%S%.f")),
+            Err(_) => f.write_str("null"),
+        },
+        BoltType::DateTimeZoneId(dt) => match NaiveDateTime::try_from(dt) {
+            Ok(local) => write!(
+                f,
+                "datetime('{}[{}]')",
+                local.format("%Y-%m-%dT%H:%M:%S%.f"),
+                dt.tz_id()
+            ),
+            Err(_) => f.write_str("null"),
+        },
+    }
+}
+
+const REDACTED: &str = "'<redacted>'";
+
+/// Whether a parameter is named in `redact`, on its own or after a prefix.
+fn is_redacted(name: &str, redact: &[&str]) -> bool {
+    redact.iter().any(|r| {
+        name == *r
+            || name
+                .strip_suffix(r)
+                .is_some_and(|prefix| prefix.ends_with('_'))
+    })
+}
+
+fn write_float(f: &mut impl Write, x: f64) -> fmt::Result {
+    if x.is_nan() {
+        f.write_str("0.0 / 0.0")
+    } else if x.is_infinite() {
+        f.write_str(if x > 0.0 { "1.0 / 0.0" } else { "-1.0 / 0.0" })
+    } else {
+        write!(f, "{:?}", x)
+    }
+}
+
+fn write_string(f: &mut impl Write, s: &str) -> fmt::Result {
+    f.write_char('\'')?;
+    for c in s.chars() {
+        match c {
+            '\'' => f.write_str("\\'")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04X}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('\'')
+}
+
+fn write_list<W: Write, T>(
+    f: &mut W,
+    items: impl Iterator<Item = T>,
+    write_item: impl Fn(&mut W, T) -> fmt::Result,
+) -> fmt::Result {
+    f.write_char('[')?;
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write_item(f, item)?;
+    }
+    f.write_char(']')
+}
+
+fn write_map(f: &mut impl Write, map: &BoltMap, redact: &[&str]) -> fmt::Result {
+    let mut entries: Vec<_> = map.value.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.value.cmp(&b.value));
+    f.write_char('{')?;
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write_key(f, &key.value)?;
+        f.write_str(": ")?;
+        if redact.contains(&key.value.as_str()) {
+            f.write_str(REDACTED)?;
+        } else {
+            write_literal(f, value, redact)?;
+        }
+    }
+    f.write_char('}')
+}
+
+/// Writes a map key, in backticks unless it's a plain identifier.
+fn write_key(f: &mut impl Write, key: &str) -> fmt::Result {
+    let plain = key.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if plain {
+        f.write_str(key)
+    } else {
+        write!(f, "`{}`", key.replace('`', "``"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn display() {
+        let map: BoltMap = [("b", BoltType::from(1)), ("a-b", BoltType::from(true))]
+            .into_iter()
+            .map(|(k, v)| (k.into(), v))
+            .collect();
+        let q = CypherQuery::new("MATCH (n { name: $name }) RETURN n")
+            .param("name", "it's \"a\"\n\\")
+            .param("list", vec![1.5f64, 2.0])
+            .param("map", BoltType::Map(map))
+            .param("null", BoltType::Null(Default::default()))
+            .param(
+                "at",
+                Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0)
+                    .unwrap()
+                    .fixed_offset(),
+            )
+            .param("day", NaiveDate::from_ymd_opt(2021, 1, 1).unwrap());
+        assert_eq!(
+            q.to_string(),
+            "MATCH (n { name: $name }) RETURN n\n\
+             // $at = datetime('2021-01-01T00:00:00+00:00')\n\
+             // $day = date('2021-01-01')\n\
+             // $list = [1.5, 2.0]\n\
+             // $map = {`a-b`: true, b: 1}\n\
+             // $name = 'it\\'s \"a\"\\n\\\\'\n\
+             // $null = null"
+        );
+        let q: Query = q.into();
+        assert!(q.has_param_key("null"));
+    }
+
+    #[test]
+    fn inline_params() {
+        let row: BoltMap = [("name", "Bob"), ("password", "hunter2")]
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        let q = CypherQuery::new(
+            "MATCH (n { name: $name, password: $n_password }) \
+             WHERE n.note <> '$name' AND n.x IN $rows \
+             RETURN n, $missing, $name_2",
+        )
+        .param("name", "O'Brien")
+        .param("name_2", 2)
+        .param("n_password", "hunter2")
+        .param("rows", vec![BoltType::Map(row)]);
+        assert_eq!(
+            q.inline_params_unsafe(&["password"]),
+            "MATCH (n { name: 'O\\'Brien', password: '<redacted>' }) \
+             WHERE n.note <> '$name' AND n.x IN [{name: 'Bob', password: '<redacted>'}] \
+             RETURN n, $missing, 2"
+        );
+        assert!(q.inline_params_unsafe(&[]).contains("'hunter2' }"));
+    }
+
+    #[test]
+    fn inline_escaped_params() {
+        let q = CypherQuery::new(
+            "CREATE (n { `zip-code`: $`n_zip-code`, `a``b`: $`a``b`, pin: $`n_pin-code` }) \
+             RETURN $`missing-name`",
+        )
+        .param("n_zip-code", "12345")
+        .param("a`b", 1)
+        .param("n_pin-code", "0000");
+        assert_eq!(
+            q.inline_params_unsafe(&["pin-code"]),
+            "CREATE (n { `zip-code`: '12345', `a``b`: 1, pin: '<redacted>' }) \
+             RETURN $`missing-name`"
+        );
+    }
+}
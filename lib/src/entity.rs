@@ -1,5 +1,8 @@
-use crate::{format_query_fields, Stamps};
-use neo4rs::{Query, Row};
+use crate::{
+    format::{format_labels, format_row_fields},
+    format_query_fields, Stamps,
+};
+use neo4rs::{BoltMap, BoltType, Query, Row};
 
 /// The full or partial fields on a node or relationship that may have timestamps.
 ///
@@ -52,10 +55,15 @@ pub trait FieldSet: TryFrom<Row> {
     /// Adds all field values to the query parameters, matching placeholders in [as_query_fields()].
     fn add_values_to_params(&self, query: Query, prefix: Option<&str>, mode: StampMode) -> Query;
 
+    /// Puts all field values in a map keyed by field name, the same values [add_values_to_params()] would add.
+    ///
+    /// This is one row of the `$rows` parameter used by the batch queries, e.g. [NodeEntity::create_many].
+    fn to_bolt_map(&self, mode: StampMode) -> BoltMap;
+
     /// Formatted like `typename() { as_query_fields() }`, or for a fieldless relationship, just `typename()`.
     fn to_query_obj(prefix: Option<&str>, mode: StampMode) -> String {
         let fields = Self::to_query_fields(prefix, mode);
-        let labels = Self::labels().join(":");
+        let labels = format_labels(Self::labels());
         if fields.is_empty() {
             return labels;
         }
@@ -78,13 +86,88 @@ pub enum StampMode {
     ///
     /// Applies to both [Stamps::Updated] and [Stamps::Both].
     Update,
+    /// For the `ON MATCH` branch of a `MERGE`. The updated stamp is hardcoded to `datetime()`,
+    /// and the created stamp is omitted so the existing value is kept.
+    ///
+    /// The `ON CREATE` branch uses [StampMode::Create], which needs the same parameters.
+    Merge,
+}
+
+/// Formats the `ON CREATE SET` and `ON MATCH SET` parts of a `MERGE` for `var`.
+///
+/// Either part is left out when it has no fields. The parameters for both are added with [StampMode::Merge].
+pub(crate) fn format_merge_sets<T: FieldSet>(var: &str, prefix: Option<&str>) -> String {
+    let mut sets = String::new();
+    let on_create = T::to_query_fields(prefix, StampMode::Create);
+    if !on_create.is_empty() {
+        sets.push_str(&format!(" ON CREATE SET {} += {{ {} }}", var, on_create));
+    }
+    let on_match = T::to_query_fields(prefix, StampMode::Merge);
+    if !on_match.is_empty() {
+        sets.push_str(&format!(" ON MATCH SET {} += {{ {} }}", var, on_match));
+    }
+    sets
+}
+
+/// Formats `Labels { field: row.field, ... }` for matching on every field of `T` in a batch query.
+pub(crate) fn format_row_obj<T: FieldSet>(row: &str) -> String {
+    let fields = format_row_fields(T::field_names(), row);
+    let labels = format_labels(T::labels());
+    if fields.is_empty() {
+        return labels;
+    }
+    format!("{} {{ {} }}", labels, fields)
+}
+
+/// Formats setting `var` from `row` in a batch query, e.g. `n = row, n += { created_at: datetime() }`.
+///
+/// `op` is either `=` or `+=`. Timestamps that are hardcoded in the [StampMode] are added after the row.
+pub(crate) fn format_row_sets<T: FieldSet>(
+    var: &str,
+    row: &str,
+    op: &str,
+    mode: StampMode,
+) -> String {
+    let stamps = T::timestamps().0.as_query_fields(None, mode);
+    if stamps.is_empty() {
+        return format!("{} {} {}", var, op, row);
+    }
+    format!("{} {} {}, {} += {{ {} }}", var, op, row, var, stamps)
+}
+
+/// Formats a batch query that runs `body` once for each `row` in `$rows`.
+///
+/// With a `chunk_size`, `body` runs in a `CALL { ... } IN TRANSACTIONS OF n ROWS` subquery.
+/// Those must be run in an implicit (auto-commit) transaction, e.g. with [neo4rs::Graph::run].
+pub(crate) fn format_unwind(body: &str, chunk_size: Option<usize>) -> String {
+    match chunk_size {
+        Some(n) => format!(
+            "UNWIND $rows AS row CALL {{ WITH row {} }} IN TRANSACTIONS OF {} ROWS",
+            body, n
+        ),
+        None => format!("UNWIND $rows AS row {}", body),
+    }
+}
+
+/// Formats a batch query that runs `body` once for each `row` in `$rows`, with `i` as the row's index.
+pub(crate) fn format_unwind_indexed(body: &str) -> String {
+    format!(
+        "UNWIND range(0, size($rows) - 1) AS i WITH i, $rows[i] AS row {}",
+        body
+    )
+}
+
+/// Converts the rows of a batch query into the `$rows` parameter.
+pub(crate) fn rows_param(rows: impl IntoIterator<Item = BoltMap>) -> BoltType {
+    BoltType::from(rows.into_iter().map(BoltType::Map).collect::<Vec<_>>())
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
-    use crate::{format_param, Error, Neo4jMap};
+    use crate::{format_param, Error, Neo4jMap, NodeEntity, NodeId, RelationEntity, RelationId};
     use chrono::{DateTime, Utc};
+    use neo4rs::{Node, Relation, UnboundedRelation};
 
     pub struct Foo {
         pub name: String,
@@ -128,6 +211,13 @@ pub(crate) mod tests {
                 .param(&format_param("name", prefix), self.name.clone())
                 .param(&format_param("age", prefix), self.age as i64)
         }
+
+        fn to_bolt_map(&self, _: StampMode) -> BoltMap {
+            let mut map = BoltMap::new();
+            map.put("name".into(), self.name.clone().into());
+            map.put("age".into(), (self.age as i64).into());
+            map
+        }
     }
     impl TryFrom<Row> for Foo {
         type Error = Error;
@@ -177,7 +267,7 @@ pub(crate) mod tests {
             mode: StampMode,
         ) -> Query {
             match mode {
-                StampMode::Create => query,
+                StampMode::Create | StampMode::Merge => query,
                 StampMode::Read => query
                     .param(
                         &format_param("created", prefix),
@@ -193,6 +283,19 @@ pub(crate) mod tests {
                 ),
             }
         }
+
+        fn to_bolt_map(&self, mode: StampMode) -> BoltMap {
+            let mut map = BoltMap::new();
+            match mode {
+                StampMode::Create | StampMode::Merge => (),
+                StampMode::Read => {
+                    map.put("created".into(), self.created.fixed_offset().into());
+                    map.put("updated".into(), self.updated.fixed_offset().into());
+                }
+                StampMode::Update => map.put("created".into(), self.created.fixed_offset().into()),
+            }
+            map
+        }
     }
     impl TryFrom<Row> for Bar {
         type Error = Error;
@@ -232,6 +335,10 @@ pub(crate) mod tests {
         fn add_values_to_params(&self, query: Query, _: Option<&str>, _: StampMode) -> Query {
             query
         }
+
+        fn to_bolt_map(&self, _: StampMode) -> BoltMap {
+            BoltMap::new()
+        }
     }
     impl TryFrom<Row> for Baz {
         type Error = Error;
@@ -240,6 +347,78 @@ pub(crate) mod tests {
         }
     }
 
+    //
+    // Foo and Bar as nodes, and Baz as a relationship between them
+    //
+    impl TryFrom<Node> for Foo {
+        type Error = Error;
+        fn try_from(value: Node) -> Result<Self, Self::Error> {
+            Ok(Self {
+                name: value
+                    .get("name")
+                    .map_err(|_e| Error::MissingField("name".to_owned()))?,
+                age: value
+                    .get("age")
+                    .map_err(|_e| Error::MissingField("age".to_owned()))?,
+            })
+        }
+    }
+    impl NodeEntity for Foo {
+        type Id = Foo;
+        fn identifier(&self) -> Self::Id {
+            Foo {
+                name: self.name.clone(),
+                age: self.age,
+            }
+        }
+    }
+    impl NodeId for Foo {
+        type T = Foo;
+    }
+    impl TryFrom<Node> for Bar {
+        type Error = Error;
+        fn try_from(value: Node) -> Result<Self, Self::Error> {
+            let map = Neo4jMap::Node(&value);
+            Ok(Self {
+                created: map.get_timestamp("created")?,
+                updated: map.get_timestamp("updated")?,
+            })
+        }
+    }
+    impl NodeEntity for Bar {
+        type Id = Bar;
+        fn identifier(&self) -> Self::Id {
+            Bar {
+                created: self.created,
+                updated: self.updated,
+            }
+        }
+    }
+    impl NodeId for Bar {
+        type T = Bar;
+    }
+    impl TryFrom<Relation> for Baz {
+        type Error = Error;
+        fn try_from(_: Relation) -> Result<Self, Self::Error> {
+            Ok(Self)
+        }
+    }
+    impl TryFrom<UnboundedRelation> for Baz {
+        type Error = Error;
+        fn try_from(_: UnboundedRelation) -> Result<Self, Self::Error> {
+            Ok(Self)
+        }
+    }
+    impl RelationEntity for Baz {
+        type Id = Baz;
+        fn identifier(&self) -> Self::Id {
+            Baz
+        }
+    }
+    impl RelationId for Baz {
+        type T = Baz;
+    }
+
     #[test]
     fn as_obj() {
         // Foo
@@ -260,6 +439,14 @@ pub(crate) mod tests {
             Bar::to_query_obj(None, StampMode::Update),
             "Bar { created: $created, updated: datetime() }"
         );
+        assert_eq!(
+            Bar::to_query_obj(None, StampMode::Merge),
+            "Bar { updated: datetime() }"
+        );
+        assert_eq!(
+            Foo::to_query_obj(None, StampMode::Merge),
+            "Foo:FooLabel2 { name: $name, age: $age }"
+        );
         // Baz
         assert_eq!(Baz::to_query_obj(None, StampMode::Read), "BAZ");
     }
@@ -310,6 +497,14 @@ pub(crate) mod tests {
         assert!(q.has_param_key("created"));
         assert!(!q.has_param_key("updated"));
 
+        let mut q = Query::new(format!(
+            "MERGE (n:{})",
+            Bar::to_query_obj(None, StampMode::Merge)
+        ));
+        q = bar.add_values_to_params(q, None, StampMode::Merge);
+        assert!(!q.has_param_key("created"));
+        assert!(!q.has_param_key("updated"));
+
         // Baz
         let mut q = Query::new(format!(
             "MATCH (s:{})
@@ -327,6 +522,48 @@ pub(crate) mod tests {
         assert!(q.has_param_key("e_created"));
     }
 
+    #[test]
+    fn batch() {
+        assert_eq!(
+            format_row_obj::<Foo>("row"),
+            "Foo:FooLabel2 { name: row.name, age: row.age }"
+        );
+        assert_eq!(format_row_obj::<Baz>("row.r"), "BAZ");
+        assert_eq!(
+            format_row_sets::<Foo>("n", "row", "=", StampMode::Create),
+            "n = row"
+        );
+        assert_eq!(
+            format_row_sets::<Bar>("n", "row", "=", StampMode::Create),
+            "n = row, n += { created: datetime(), updated: datetime() }"
+        );
+        assert_eq!(
+            format_row_sets::<Bar>("r", "row.r", "+=", StampMode::Merge),
+            "r += row.r, r += { updated: datetime() }"
+        );
+        assert_eq!(
+            format_unwind("CREATE (n:Foo)", None),
+            "UNWIND $rows AS row CREATE (n:Foo)"
+        );
+        assert_eq!(
+            format_unwind("CREATE (n:Foo)", Some(100)),
+            "UNWIND $rows AS row CALL { WITH row CREATE (n:Foo) } IN TRANSACTIONS OF 100 ROWS"
+        );
+
+        let bar = Bar {
+            created: Utc::now(),
+            updated: Utc::now(),
+        };
+        assert!(bar.to_bolt_map(StampMode::Create).value.is_empty());
+        assert!(bar.to_bolt_map(StampMode::Merge).value.is_empty());
+        assert_eq!(bar.to_bolt_map(StampMode::Update).value.len(), 1);
+        assert_eq!(bar.to_bolt_map(StampMode::Read).value.len(), 2);
+        match rows_param([bar.to_bolt_map(StampMode::Read)]) {
+            BoltType::List(rows) => assert_eq!(rows.len(), 1),
+            _ => panic!("expected a list"),
+        }
+    }
+
     #[test]
     fn number_types() {
         let num_types = NumTypes {
@@ -547,7 +784,43 @@ pub(crate) mod tests {
                 .param(&format_param("f32_opt", prefix), self.f32_opt)
                 .param(&format_param("f64_opt", prefix), self.f64_opt)
         }
+
+        fn to_bolt_map(&self, _: StampMode) -> BoltMap {
+            let values: [(&str, BoltType); 28] = [
+                ("usize_num", (self.usize_num as i64).into()),
+                ("isize_num", (self.isize_num as i64).into()),
+                ("u8_num", (self.u8_num as u16).into()),
+                ("u16_num", self.u16_num.into()),
+                ("u32_num", self.u32_num.into()),
+                ("u64_num", (self.u64_num as i64).into()),
+                ("u128_num", (self.u128_num as i64).into()),
+                ("i8_num", self.i8_num.into()),
+                ("i16_num", self.i16_num.into()),
+                ("i32_num", self.i32_num.into()),
+                ("i64_num", self.i64_num.into()),
+                ("i128_num", (self.i128_num as i64).into()),
+                ("f32_num", self.f32_num.into()),
+                ("f64_num", self.f64_num.into()),
+                ("usize_opt", self.usize_opt.map(|v| v as i64).into()),
+                ("isize_opt", self.isize_opt.map(|v| v as i64).into()),
+                ("u8_opt", self.u8_opt.map(|v| v as u16).into()),
+                ("u16_opt", self.u16_opt.into()),
+                ("u32_opt", self.u32_opt.into()),
+                ("u64_opt", self.u64_opt.map(|v| v as i64).into()),
+                ("u128_opt", self.u128_opt.map(|v| v as i64).into()),
+                ("i8_opt", self.i8_opt.into()),
+                ("i16_opt", self.i16_opt.into()),
+                ("i32_opt", self.i32_opt.into()),
+                ("i64_opt", self.i64_opt.into()),
+                ("i128_opt", self.i128_opt.map(|v| v as i64).into()),
+                ("f32_opt", self.f32_opt.into()),
+                ("f64_opt", self.f64_opt.into()),
+            ];
+            values.into_iter().map(|(k, v)| (k.into(), v)).collect()
+        }
     }
+    // Mirrors the macro output, which is generic over the number types.
+    #[allow(clippy::unnecessary_fallible_conversions, clippy::manual_ok_err)]
     impl TryFrom<Row> for NumTypes {
         type Error = Error;
         fn try_from(value: Row) -> Result<Self, Self::Error> {
@@ -11,4 +11,8 @@ pub enum Error {
     FromMapInner(String),
     #[error("Error building field {1} on {0}")]
     BuilderError(String, String),
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
 }
@@ -0,0 +1,482 @@
+use crate::{escape_identifier, format::format_placeholder, CypherQuery, Error, IntoBolt};
+use chrono::{DateTime, FixedOffset, Utc};
+use neo4rs::BoltType;
+use std::{collections::BTreeMap, marker::PhantomData};
+
+/// A reference to one property of a DTO, for use in predicates, `ORDER BY` and `RETURN`.
+///
+/// The derive macros generate these for each field, e.g. `Person::fields().age` is a `Field<Option<u8>>`.
+/// In a query builder, a field refers to the variable bound to its DTO type, unless one is chosen with [Field::of].
+///
+/// Comparisons build an `O`, which is a [Predicate] except for the fields of a [Filter](crate::Filter),
+/// see [FilterField](crate::FilterField).
+pub struct Field<T, O = Predicate> {
+    owner: &'static str,
+    name: &'static str,
+    var: Option<String>,
+    _type: PhantomData<fn() -> (T, O)>,
+}
+impl<T, O> Clone for Field<T, O> {
+    fn clone(&self) -> Self {
+        Self {
+            owner: self.owner,
+            name: self.name,
+            var: self.var.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+impl<T, O> std::fmt::Debug for Field<T, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Field")
+            .field("owner", &self.owner)
+            .field("name", &self.name)
+            .field("var", &self.var)
+            .finish()
+    }
+}
+impl<T, O> Field<T, O> {
+    /// A field named `name` on the DTO whose [typename](crate::FieldSet::typename) is `owner`.
+    pub fn new(owner: &'static str, name: &'static str) -> Self {
+        Self {
+            owner,
+            name,
+            var: None,
+            _type: PhantomData,
+        }
+    }
+    /// The property name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// The [typename](crate::FieldSet::typename) of the DTO this field belongs to.
+    pub fn owner(&self) -> &'static str {
+        self.owner
+    }
+    /// Uses this field on a specific variable, e.g. when a pattern has two nodes of the same type.
+    pub fn of(mut self, var: &str) -> Self {
+        self.var = Some(var.to_owned());
+        self
+    }
+    /// Orders by this field, ascending.
+    pub fn asc(self) -> OrderBy {
+        OrderBy {
+            expr: self.into(),
+            descending: false,
+        }
+    }
+    /// Orders by this field, descending.
+    pub fn desc(self) -> OrderBy {
+        OrderBy {
+            expr: self.into(),
+            descending: true,
+        }
+    }
+    fn compare(&self, op: &'static str, value: impl IntoBolt) -> O
+    where
+        O: From<Predicate>,
+    {
+        Predicate::compare(self.clone(), op, value).into()
+    }
+}
+impl<T: FieldValue, O: From<Predicate>> Field<T, O> {
+    pub fn eq(&self, value: T::Inner) -> O {
+        self.compare("=", value)
+    }
+    pub fn ne(&self, value: T::Inner) -> O {
+        self.compare("<>", value)
+    }
+    /// Matches any of the `values`.
+    pub fn in_(&self, values: Vec<T::Inner>) -> O {
+        self.compare("IN", values)
+    }
+}
+impl<T: FieldValue, O: From<Predicate>> Field<T, O>
+where
+    T::Inner: Ordered,
+{
+    pub fn gt(&self, value: T::Inner) -> O {
+        self.compare(">", value)
+    }
+    pub fn gte(&self, value: T::Inner) -> O {
+        self.compare(">=", value)
+    }
+    pub fn lt(&self, value: T::Inner) -> O {
+        self.compare("<", value)
+    }
+    pub fn lte(&self, value: T::Inner) -> O {
+        self.compare("<=", value)
+    }
+}
+impl<T: FieldValue<Inner = String>, O: From<Predicate>> Field<T, O> {
+    pub fn starts_with(&self, value: impl Into<String>) -> O {
+        self.compare("STARTS WITH", value.into())
+    }
+    pub fn ends_with(&self, value: impl Into<String>) -> O {
+        self.compare("ENDS WITH", value.into())
+    }
+    pub fn contains(&self, value: impl Into<String>) -> O {
+        self.compare("CONTAINS", value.into())
+    }
+}
+impl<T, O: From<Predicate>> Field<Option<T>, O> {
+    pub fn is_null(&self) -> O {
+        Predicate::IsNull(self.clone().into(), true).into()
+    }
+    pub fn is_not_null(&self) -> O {
+        Predicate::IsNull(self.clone().into(), false).into()
+    }
+}
+
+/// The field types that can be compared with a value.
+///
+/// For `Option<T>` fields, the value is a `T`.
+pub trait FieldValue {
+    type Inner: IntoBolt;
+}
+impl<T: FieldValue> FieldValue for Option<T> {
+    type Inner = T::Inner;
+}
+impl<T: IntoBolt> FieldValue for Vec<T> {
+    type Inner = Vec<T>;
+}
+
+/// Field types that can be compared with `<`, `>`, etc.
+pub trait Ordered {}
+
+macro_rules! impl_field_value {
+    ($($ty:ty),*) => {
+        $(
+            impl FieldValue for $ty {
+                type Inner = $ty;
+            }
+        )*
+    };
+}
+macro_rules! impl_ordered {
+    ($($ty:ty),*) => {
+        $(
+            impl Ordered for $ty {}
+        )*
+        impl_field_value!($($ty),*);
+    };
+}
+impl_field_value!(String, bool);
+impl_ordered!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    DateTime<Utc>,
+    DateTime<FixedOffset>
+);
+
+/// A Cypher expression.
+#[derive(Clone, Debug)]
+pub enum Expr {
+    /// A property of a DTO, e.g. `p.name`.
+    Property {
+        owner: &'static str,
+        name: &'static str,
+        var: Option<String>,
+    },
+    /// A variable, e.g. `p`.
+    Var(String),
+    /// A value, which is passed as a parameter.
+    Value(BoltType),
+}
+impl<T, O> From<Field<T, O>> for Expr {
+    fn from(field: Field<T, O>) -> Self {
+        Expr::Property {
+            owner: field.owner,
+            name: field.name,
+            var: field.var,
+        }
+    }
+}
+impl Expr {
+    /// Formats this expression, adding any values to `params`.
+    pub(crate) fn render(&self, scope: &Scope, params: &mut Params) -> Result<String, Error> {
+        self.render_named(scope, params, "value")
+    }
+
+    /// Same as [Expr::render], with a suggested parameter name for values.
+    fn render_named(
+        &self,
+        scope: &Scope,
+        params: &mut Params,
+        name: &str,
+    ) -> Result<String, Error> {
+        Ok(match self {
+            Expr::Property { owner, name, var } => {
+                format!(
+                    "{}.{}",
+                    scope.resolve(owner, name, var.as_deref())?,
+                    escape_identifier(name)
+                )
+            }
+            Expr::Var(var) => var.clone(),
+            Expr::Value(value) => format_placeholder(&params.add(name, value.clone())),
+        })
+    }
+
+    /// A name for the column or parameter that holds this expression, e.g. `p_name` for `p.name`.
+    pub(crate) fn alias(&self, scope: &Scope) -> Result<String, Error> {
+        Ok(match self {
+            Expr::Property { owner, name, var } => {
+                format!("{}_{}", scope.resolve(owner, name, var.as_deref())?, name)
+            }
+            Expr::Var(var) => var.clone(),
+            Expr::Value(_) => "value".to_owned(),
+        })
+    }
+}
+
+/// A condition for a `WHERE` clause.
+///
+/// Predicates are built from [Field]s, e.g. `Person::fields().age.gt(30)`, and combined with
+/// [Predicate::and], [Predicate::or] and [Predicate::not].
+#[derive(Clone, Debug)]
+pub enum Predicate {
+    /// `left op right`, e.g. `p.age > $p_age`.
+    Compare(Expr, &'static str, Expr),
+    /// `expr IS NULL` if true, otherwise `expr IS NOT NULL`.
+    IsNull(Expr, bool),
+    And(Vec<Predicate>),
+    Or(Vec<Predicate>),
+    Not(Box<Predicate>),
+}
+impl Predicate {
+    fn compare(field: impl Into<Expr>, op: &'static str, value: impl IntoBolt) -> Self {
+        Predicate::Compare(field.into(), op, Expr::Value(value.into_bolt()))
+    }
+    /// Both this and `other` must be true.
+    pub fn and(self, other: Predicate) -> Self {
+        match self {
+            Predicate::And(mut all) => {
+                all.push(other);
+                Predicate::And(all)
+            }
+            this => Predicate::And(vec![this, other]),
+        }
+    }
+    /// Either this or `other` must be true.
+    pub fn or(self, other: Predicate) -> Self {
+        match self {
+            Predicate::Or(mut any) => {
+                any.push(other);
+                Predicate::Or(any)
+            }
+            this => Predicate::Or(vec![this, other]),
+        }
+    }
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Predicate::Not(Box::new(self))
+    }
+
+    /// Formats this predicate, adding any values to `params`.
+    pub(crate) fn render(&self, scope: &Scope, params: &mut Params) -> Result<String, Error> {
+        Ok(match self {
+            Predicate::Compare(left, op, right) => {
+                let name = left.alias(scope)?;
+                let left = left.render(scope, params)?;
+                let right = right.render_named(scope, params, &name)?;
+                format!("{} {} {}", left, op, right)
+            }
+            Predicate::IsNull(expr, true) => format!("{} IS NULL", expr.render(scope, params)?),
+            Predicate::IsNull(expr, false) => {
+                format!("{} IS NOT NULL", expr.render(scope, params)?)
+            }
+            Predicate::And(all) => Self::join(all, " AND ", scope, params)?,
+            Predicate::Or(any) => Self::join(any, " OR ", scope, params)?,
+            Predicate::Not(inner) => format!("NOT ({})", inner.render(scope, params)?),
+        })
+    }
+
+    fn join(
+        items: &[Predicate],
+        sep: &str,
+        scope: &Scope,
+        params: &mut Params,
+    ) -> Result<String, Error> {
+        let items = items
+            .iter()
+            .map(|p| p.render(scope, params))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("({})", items.join(sep)))
+    }
+}
+
+/// One item of an `ORDER BY` clause, see [Field::asc] and [Field::desc].
+#[derive(Clone, Debug)]
+pub struct OrderBy {
+    pub(crate) expr: Expr,
+    pub(crate) descending: bool,
+}
+impl OrderBy {
+    pub(crate) fn render(&self, scope: &Scope, params: &mut Params) -> Result<String, Error> {
+        let expr = self.expr.render(scope, params)?;
+        if self.descending {
+            Ok(format!("{} DESC", expr))
+        } else {
+            Ok(expr)
+        }
+    }
+}
+
+/// The variables bound in a query, used to resolve which variable a [Field] refers to.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Scope {
+    /// The typename and variable of each bound node or relationship.
+    bindings: Vec<(&'static str, String)>,
+    /// The variable for fields whose owner isn't bound, if any.
+    fallback: Option<String>,
+}
+impl Scope {
+    /// A scope where every field is on `var`, unless it has its own variable.
+    pub(crate) fn only(var: &str) -> Self {
+        Self {
+            bindings: Vec::new(),
+            fallback: Some(var.to_owned()),
+        }
+    }
+    pub(crate) fn bind(&mut self, typename: &'static str, var: &str) {
+        self.bindings.push((typename, var.to_owned()));
+    }
+    pub(crate) fn contains(&self, var: &str) -> bool {
+        self.bindings.iter().any(|(_, v)| v == var)
+    }
+    /// The variable for a field: either its own, or the first one bound to its owner.
+    fn resolve(&self, owner: &str, name: &str, var: Option<&str>) -> Result<String, Error> {
+        if let Some(var) = var {
+            return Ok(var.to_owned());
+        }
+        self.bindings
+            .iter()
+            .find(|(typename, _)| *typename == owner)
+            .map(|(_, var)| var)
+            .or(self.fallback.as_ref())
+            .cloned()
+            .ok_or_else(|| {
+                Error::InvalidQuery(format!(
+                    "No variable is bound to {} for the field {}. Use `.of(var)` to choose one.",
+                    owner, name
+                ))
+            })
+    }
+}
+
+/// The parameters of a query, named so that they don't collide.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Params {
+    values: BTreeMap<String, BoltType>,
+}
+impl Params {
+    /// Adds a parameter named `name`, or `name_2`, `name_3`, etc. if it's taken. Returns the name used.
+    pub(crate) fn add(&mut self, name: &str, value: BoltType) -> String {
+        let mut unique = name.to_owned();
+        let mut n = 1;
+        while self.values.contains_key(&unique) {
+            n += 1;
+            unique = format!("{}_{}", name, n);
+        }
+        self.values.insert(unique.clone(), value);
+        unique
+    }
+    pub(crate) fn into_inner(self) -> BTreeMap<String, BoltType> {
+        self.values
+    }
+    /// A [CypherQuery] with these parameters.
+    pub(crate) fn into_query(self, text: String) -> CypherQuery {
+        CypherQuery {
+            text,
+            params: self.values,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn age() -> Field<Option<u8>> {
+        Field::new("Person", "age")
+    }
+    fn name() -> Field<String> {
+        Field::new("Person", "name")
+    }
+
+    #[test]
+    fn predicates() {
+        let mut scope = Scope::default();
+        scope.bind("Person", "p");
+        let mut params = Params::default();
+        let pred = age()
+            .gt(30)
+            .and(age().lt(40))
+            .and(
+                name()
+                    .starts_with("A")
+                    .or(name().eq("Bob".to_owned()).not()),
+            )
+            .and(age().of("q").is_null());
+        assert_eq!(
+            pred.render(&scope, &mut params).unwrap(),
+            "(p.age > $p_age AND p.age < $p_age_2 AND (p.name STARTS WITH $p_name OR NOT (p.name = $p_name_2)) AND q.age IS NULL)"
+        );
+        let params = params.into_inner();
+        assert_eq!(
+            params.keys().collect::<Vec<_>>(),
+            ["p_age", "p_age_2", "p_name", "p_name_2"]
+        );
+        assert_eq!(params["p_age"], BoltType::from(30_i64));
+
+        let mut params = Params::default();
+        assert_eq!(
+            name()
+                .in_(vec!["a".to_owned(), "b".to_owned()])
+                .render(&scope, &mut params)
+                .unwrap(),
+            "p.name IN $p_name"
+        );
+        assert_eq!(
+            age().desc().render(&scope, &mut params).unwrap(),
+            "p.age DESC"
+        );
+    }
+
+    #[test]
+    fn param_names() {
+        // `a` + `b_c` and `a_b` + `c` both format to `a_b_c`, but get distinct parameters.
+        let mut params = Params::default();
+        assert_eq!(params.add("a_b_c", BoltType::from(1)), "a_b_c");
+        assert_eq!(params.add("a_b_c", BoltType::from(2)), "a_b_c_2");
+    }
+
+    #[test]
+    fn unbound() {
+        let err = age()
+            .is_null()
+            .render(&Scope::default(), &mut Params::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("No variable is bound to Person"));
+        assert_eq!(
+            age()
+                .is_null()
+                .render(&Scope::only("n"), &mut Params::default())
+                .unwrap(),
+            "n.age IS NULL"
+        );
+    }
+}
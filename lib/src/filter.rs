@@ -0,0 +1,117 @@
+use crate::{
+    field::{Params, Scope},
+    CypherQuery, Field, FieldSet, Predicate,
+};
+use neo4rs::BoltType;
+use std::{collections::BTreeMap, marker::PhantomData};
+
+/// A `WHERE` condition on the fields of one DTO, e.g. `Person::filter().age.gt(30)`.
+///
+/// Filters combine with [Filter::and], [Filter::or] and [Filter::not], and can be used with
+/// [NodeEntity::find](crate::NodeEntity::find), or in a [MatchQuery](crate::MatchQuery) as a [Predicate].
+pub struct Filter<T> {
+    predicate: Predicate,
+    _type: PhantomData<fn() -> T>,
+}
+impl<T> Clone for Filter<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.predicate.clone())
+    }
+}
+impl<T> std::fmt::Debug for Filter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Filter").field(&self.predicate).finish()
+    }
+}
+impl<T> From<Filter<T>> for Predicate {
+    fn from(filter: Filter<T>) -> Self {
+        filter.predicate
+    }
+}
+impl<T> From<Predicate> for Filter<T> {
+    fn from(predicate: Predicate) -> Self {
+        Self::new(predicate)
+    }
+}
+impl<T> Filter<T> {
+    fn new(predicate: Predicate) -> Self {
+        Self {
+            predicate,
+            _type: PhantomData,
+        }
+    }
+    pub fn and(self, other: Filter<T>) -> Self {
+        Self::new(self.predicate.and(other.predicate))
+    }
+    pub fn or(self, other: Filter<T>) -> Self {
+        Self::new(self.predicate.or(other.predicate))
+    }
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self::new(self.predicate.not())
+    }
+}
+impl<T: FieldSet> Filter<T> {
+    /// The `WHERE` clause for this filter on the variable `var`, and its parameters.
+    ///
+    /// Parameters are named after the variable and field, e.g. `$n_age`.
+    pub fn to_where_clause(&self, var: &str) -> (String, BTreeMap<String, BoltType>) {
+        let mut params = Params::default();
+        let clause = self.render(var, &mut params);
+        (clause, params.into_inner())
+    }
+
+    /// The `WHERE` clause, adding its parameters to `params`.
+    pub(crate) fn render(&self, var: &str, params: &mut Params) -> String {
+        // Every field of a filter is on `T`, so they all resolve to `var`.
+        let predicate = self
+            .predicate
+            .render(&Scope::only(var), params)
+            .expect("a scope with a fallback resolves every field");
+        format!("WHERE {}", predicate)
+    }
+
+    /// A query with this filter's `WHERE` clause and parameters, from a function that formats the text around it.
+    pub(crate) fn to_query(&self, var: &str, text: impl FnOnce(&str) -> String) -> CypherQuery {
+        let mut params = Params::default();
+        let clause = self.render(var, &mut params);
+        params.into_query(text(&clause))
+    }
+}
+
+/// One field of a DTO, for building a [Filter] on it.
+///
+/// The derive macros generate a struct of these, e.g. `Person::filter()`. They have the same comparisons
+/// as a [Field], which build a `Filter<E>` instead of a [Predicate].
+pub type FilterField<E, T> = Field<T, Filter<E>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::tests::Foo;
+
+    fn name() -> FilterField<Foo, String> {
+        FilterField::new("Foo", "name")
+    }
+    fn age() -> FilterField<Foo, u8> {
+        FilterField::new("Foo", "age")
+    }
+
+    #[test]
+    fn where_clause() {
+        let filter = age()
+            .gte(18)
+            .and(age().gt(65).not())
+            .and(name().contains("li").or(name().in_(vec!["Bob".to_owned()])));
+        let (clause, params) = filter.to_where_clause("p");
+        assert_eq!(
+            clause,
+            "WHERE (p.age >= $p_age AND NOT (p.age > $p_age_2) AND (p.name CONTAINS $p_name OR p.name IN $p_name_2))"
+        );
+        assert_eq!(
+            params.keys().collect::<Vec<_>>(),
+            ["p_age", "p_age_2", "p_name", "p_name_2"]
+        );
+        assert_eq!(params["p_age"], BoltType::from(18));
+    }
+}
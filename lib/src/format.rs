@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 /// Utility function for formatting part of a cypher [Query].
 ///
 /// [prefix] applies to placeholders only, not field names. For example, `foo: $foo` would become `foo: $prefix_foo`.
@@ -12,7 +14,52 @@ where
         if field.as_ref().is_empty() {
             continue;
         }
-        formatted.push(format!("{}: ${}{}", field.as_ref(), prefix, field.as_ref()));
+        formatted.push(format!(
+            "{}: {}",
+            escape_identifier(field.as_ref()),
+            format_placeholder(&format!("{}{}", prefix, field.as_ref()))
+        ));
+    }
+    formatted.join(", ")
+}
+
+/// Formats fields that read their values from a map, e.g. `foo: row.foo` in a batch query.
+pub(crate) fn format_row_fields<I, S>(fields: I, row: &str) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut formatted = Vec::new();
+    for field in fields {
+        if field.as_ref().is_empty() {
+            continue;
+        }
+        let field = escape_identifier(field.as_ref());
+        formatted.push(format!("{}: {}.{}", field, row, field));
+    }
+    formatted.join(", ")
+}
+
+/// Formats fields as `RETURN` items with columns named like their parameters, e.g. `p.foo AS p_foo`.
+///
+/// The columns can be decoded with the generated `try_from_row_prefixed()`.
+pub fn format_return_fields<I, S>(fields: I, var: &str, prefix: Option<&str>) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut formatted = Vec::new();
+    for field in fields {
+        let field = field.as_ref();
+        if field.is_empty() {
+            continue;
+        }
+        formatted.push(format!(
+            "{}.{} AS {}",
+            var,
+            escape_identifier(field),
+            escape_identifier(&format_param(field, prefix))
+        ));
     }
     formatted.join(", ")
 }
@@ -25,12 +72,109 @@ where
     S: AsRef<str>,
 {
     let fields = format_query_fields(fields, prefix);
+    let name = escape_identifier(name);
     if fields.is_empty() {
-        return name.to_owned();
+        return name.into_owned();
     }
     format!("{} {{ {} }}", name, fields)
 }
 
+/// Cypher's reserved words, which can't be used as names without backticks.
+const RESERVED: &[&str] = &[
+    "ADD",
+    "ALL",
+    "AND",
+    "AS",
+    "ASC",
+    "ASCENDING",
+    "BY",
+    "CALL",
+    "CASE",
+    "CONSTRAINT",
+    "CONTAINS",
+    "CREATE",
+    "DELETE",
+    "DESC",
+    "DESCENDING",
+    "DETACH",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "ELSE",
+    "END",
+    "ENDS",
+    "EXISTS",
+    "FALSE",
+    "FOR",
+    "IN",
+    "IS",
+    "LIMIT",
+    "MANDATORY",
+    "MATCH",
+    "MERGE",
+    "NOT",
+    "NULL",
+    "OF",
+    "ON",
+    "OPTIONAL",
+    "OR",
+    "ORDER",
+    "REMOVE",
+    "REQUIRE",
+    "RETURN",
+    "SCALAR",
+    "SET",
+    "SKIP",
+    "STARTS",
+    "THEN",
+    "TRUE",
+    "UNION",
+    "UNIQUE",
+    "UNWIND",
+    "WHEN",
+    "WHERE",
+    "WITH",
+    "XOR",
+    "YIELD",
+];
+
+/// Escapes a label, relationship type, or property name for use in a query, e.g. `` `zip-code` ``.
+///
+/// Names are returned as they are if they're valid without backticks: letters, digits and `_`, not starting
+/// with a digit, and not a reserved word. Otherwise they're wrapped in backticks, with any backticks doubled.
+pub fn escape_identifier(name: &str) -> Cow<'_, str> {
+    if is_plain(name) && !RESERVED.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("`{}`", name.replace('`', "``")))
+    }
+}
+
+/// Escapes each label and joins them, e.g. `Person:` `` `Extra Label` ``.
+pub(crate) fn format_labels(labels: &[&str]) -> String {
+    labels
+        .iter()
+        .map(|l| escape_identifier(l))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Formats a parameter placeholder, e.g. `$name`, or `` $`zip-code` `` for a name that needs escaping.
+///
+/// Reserved words are fine as parameter names, so they aren't escaped.
+pub(crate) fn format_placeholder(name: &str) -> String {
+    if is_plain(name) {
+        format!("${}", name)
+    } else {
+        format!("$`{}`", name.replace('`', "``"))
+    }
+}
+
+fn is_plain(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
 /// Formats a parameter name with or without a prefix. For example, `foo` would become `prefix_foo`.
 pub fn format_param(name: &str, prefix: Option<&str>) -> String {
     if name.is_empty() {
@@ -50,6 +194,33 @@ fn prefix_str(prefix: Option<&str>) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn return_fields() {
+        assert_eq!(
+            format_return_fields(["foo", "zip-code"], "p", Some("p")),
+            "p.foo AS p_foo, p.`zip-code` AS `p_zip-code`"
+        );
+        assert_eq!(format_return_fields(["foo"], "p", None), "p.foo AS foo");
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_identifier("name_2"), "name_2");
+        assert_eq!(escape_identifier("zip-code"), "`zip-code`");
+        assert_eq!(escape_identifier("My Label"), "`My Label`");
+        assert_eq!(escape_identifier("match"), "`match`");
+        assert_eq!(escape_identifier("2fa"), "`2fa`");
+        assert_eq!(escape_identifier("a`b"), "`a``b`");
+        assert_eq!(format_placeholder("match"), "$match");
+        assert_eq!(format_placeholder("n_zip-code"), "$`n_zip-code`");
+        assert_eq!(format_labels(&["Person", "My Label"]), "Person:`My Label`");
+        assert_eq!(
+            format_query_fields(["zip-code"], Some("n")),
+            "`zip-code`: $`n_zip-code`"
+        );
+        assert_eq!(format_row_fields(["order"], "row"), "`order`: row.`order`");
+    }
+
     #[test]
     fn params() {
         assert_eq!(format_param("foo", None), "foo");
@@ -73,6 +244,16 @@ mod tests {
         assert_eq!(format_query_fields(["", ""], Some("n")), "");
     }
 
+    #[test]
+    fn row_fields() {
+        assert_eq!(
+            format_row_fields(["foo", "bar"], "row"),
+            "foo: row.foo, bar: row.bar"
+        );
+        assert_eq!(format_row_fields(["foo"], "row.s"), "foo: row.s.foo");
+        assert_eq!(format_row_fields(["", ""], "row"), "");
+    }
+
     #[test]
     fn obj() {
         assert_eq!(
@@ -0,0 +1,251 @@
+use crate::Error;
+use chrono::{DateTime, FixedOffset, Utc};
+use neo4rs::{BoltMap, BoltType, Node, Path, Relation, Row, UnboundedRelation};
+
+/// Decodes a value returned from Neo4j, such as one column of a [Row].
+///
+/// Implemented for common Rust types, [Option], [Vec] (e.g. from `collect()`), pairs (from two-item lists), the neo4rs graph types,
+/// and any type that derives `Node` or `Relation`. Used by `#[derive(CypherRow)]`.
+pub trait FromBolt: Sized {
+    /// Decodes a non-null value.
+    fn from_bolt(value: BoltType) -> Result<Self, Error>;
+
+    /// Called when a value is missing or null. This is an error unless the type is an [Option].
+    fn from_missing(name: &str) -> Result<Self, Error> {
+        Err(Error::MissingField(name.to_owned()))
+    }
+
+    /// Decodes the column `name` from a [Row].
+    fn from_column(row: &Row, name: &str) -> Result<Self, Error> {
+        from_named(row.get::<BoltType>(name).ok(), name)
+    }
+
+    /// Removes the value for `name` from a map and decodes it, e.g. one key of a map projection.
+    fn from_map_key(map: &mut BoltMap, name: &str) -> Result<Self, Error> {
+        from_named(map.value.remove(name), name)
+    }
+}
+
+/// Decodes a named value, using `name` for errors about the value itself.
+fn from_named<T: FromBolt>(value: Option<BoltType>, name: &str) -> Result<T, Error> {
+    match value {
+        None | Some(BoltType::Null(_)) => T::from_missing(name),
+        Some(value) => T::from_bolt(value).map_err(|e| match e {
+            Error::TypeMismatch(_) => Error::TypeMismatch(name.to_owned()),
+            e => e,
+        }),
+    }
+}
+
+impl<T: FromBolt> FromBolt for Option<T> {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        match value {
+            BoltType::Null(_) => Ok(None),
+            value => T::from_bolt(value).map(Some),
+        }
+    }
+    fn from_missing(_name: &str) -> Result<Self, Error> {
+        Ok(None)
+    }
+}
+
+impl<T: FromBolt> FromBolt for Vec<T> {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        match value {
+            BoltType::List(list) => list.into_iter().map(T::from_bolt).collect(),
+            _ => Err(mismatch::<Self>()),
+        }
+    }
+}
+
+/// A list of two values, e.g. `[key, count]`.
+impl<A: FromBolt, B: FromBolt> FromBolt for (A, B) {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        let values = match value {
+            BoltType::List(list) if list.len() == 2 => list.value,
+            _ => return Err(mismatch::<Self>()),
+        };
+        let mut values = values.into_iter();
+        Ok((
+            from_named(values.next(), "0")?,
+            from_named(values.next(), "1")?,
+        ))
+    }
+}
+
+impl FromBolt for BoltType {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        Ok(value)
+    }
+}
+
+impl FromBolt for BoltMap {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        match value {
+            BoltType::Map(map) => Ok(map),
+            _ => Err(mismatch::<Self>()),
+        }
+    }
+}
+
+impl FromBolt for String {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        match value {
+            BoltType::String(s) => Ok(s.value),
+            _ => Err(mismatch::<Self>()),
+        }
+    }
+}
+
+impl FromBolt for bool {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        match value {
+            BoltType::Boolean(b) => Ok(b.value),
+            _ => Err(mismatch::<Self>()),
+        }
+    }
+}
+
+/// Integers are converted, e.g. for `sum()` over integer properties.
+impl FromBolt for f64 {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        match value {
+            BoltType::Float(f) => Ok(f.value),
+            BoltType::Integer(i) => Ok(i.value as f64),
+            _ => Err(mismatch::<Self>()),
+        }
+    }
+}
+
+impl FromBolt for f32 {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        f64::from_bolt(value).map(|f| f as f32)
+    }
+}
+
+/// Integers are stored as i64 and checked when converting to the target type.
+macro_rules! impl_from_bolt_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FromBolt for $ty {
+                fn from_bolt(value: BoltType) -> Result<Self, Error> {
+                    match value {
+                        BoltType::Integer(i) => {
+                            <$ty>::try_from(i.value).map_err(|_| mismatch::<Self>())
+                        }
+                        _ => Err(mismatch::<Self>()),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_from_bolt_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl FromBolt for DateTime<FixedOffset> {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        value.try_into().map_err(|_| mismatch::<Self>())
+    }
+}
+
+impl FromBolt for DateTime<Utc> {
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        DateTime::<FixedOffset>::from_bolt(value).map(|dt| dt.into())
+    }
+}
+
+/// The neo4rs graph types, for when the raw value is needed.
+macro_rules! impl_from_bolt_graph {
+    ($($ty:ty),*) => {
+        $(
+            impl FromBolt for $ty {
+                fn from_bolt(value: BoltType) -> Result<Self, Error> {
+                    value.try_into().map_err(|_| mismatch::<Self>())
+                }
+            }
+        )*
+    };
+}
+impl_from_bolt_graph!(Node, Relation, UnboundedRelation, Path);
+
+fn mismatch<T>() -> Error {
+    Error::TypeMismatch(std::any::type_name::<T>().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neo4rs::{BoltList, BoltNull, BoltString};
+
+    fn row(columns: Vec<(&str, BoltType)>) -> Row {
+        let (fields, data): (Vec<_>, Vec<_>) = columns
+            .into_iter()
+            .map(|(k, v)| (BoltType::String(BoltString::from(k)), v))
+            .unzip();
+        Row::new(BoltList::from(fields), BoltList::from(data))
+    }
+
+    #[test]
+    fn columns() {
+        let row = row(vec![
+            ("total", BoltType::from(42)),
+            ("name", BoltType::from("Alice")),
+            ("nothing", BoltType::Null(BoltNull)),
+            (
+                "names",
+                BoltType::from(vec![BoltType::from("a"), BoltType::from("b")]),
+            ),
+        ]);
+        assert_eq!(i64::from_column(&row, "total").unwrap(), 42);
+        assert_eq!(u8::from_column(&row, "total").unwrap(), 42);
+        assert_eq!(
+            Option::<String>::from_column(&row, "name").unwrap(),
+            Some("Alice".to_owned())
+        );
+        assert_eq!(
+            Option::<String>::from_column(&row, "nothing").unwrap(),
+            None
+        );
+        assert_eq!(
+            Option::<String>::from_column(&row, "missing").unwrap(),
+            None
+        );
+        assert_eq!(
+            Vec::<String>::from_column(&row, "names").unwrap(),
+            ["a", "b"]
+        );
+        assert_eq!(
+            <(String, String)>::from_column(&row, "names").unwrap(),
+            ("a".to_owned(), "b".to_owned())
+        );
+        assert_eq!(f64::from_column(&row, "total").unwrap(), 42.0);
+        assert!(<(String, String)>::from_column(&row, "total").is_err());
+        assert!(matches!(
+            String::from_column(&row, "nothing"),
+            Err(Error::MissingField(name)) if name == "nothing"
+        ));
+        assert!(matches!(
+            String::from_column(&row, "total"),
+            Err(Error::TypeMismatch(name)) if name == "total"
+        ));
+    }
+
+    #[test]
+    fn map_keys() {
+        let mut map: BoltMap = [
+            (BoltString::from("total"), BoltType::from(42)),
+            (BoltString::from("nothing"), BoltType::Null(BoltNull)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(i64::from_map_key(&mut map, "total").unwrap(), 42);
+        assert!(matches!(
+            i64::from_map_key(&mut map, "total"),
+            Err(Error::MissingField(name)) if name == "total"
+        ));
+        assert_eq!(
+            Option::<i64>::from_map_key(&mut map, "nothing").unwrap(),
+            None
+        );
+    }
+}
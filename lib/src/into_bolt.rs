@@ -0,0 +1,93 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use neo4rs::{BoltMap, BoltNull, BoltType};
+
+/// Converts a value into a query parameter.
+///
+/// Like `Into<BoltType>`, but also covers the types that neo4rs doesn't convert, using the same casts as
+/// [FieldSet::add_values_to_params](crate::FieldSet::add_values_to_params) (e.g. `u64 as i64`).
+pub trait IntoBolt {
+    fn into_bolt(self) -> BoltType;
+}
+
+impl IntoBolt for BoltType {
+    fn into_bolt(self) -> BoltType {
+        self
+    }
+}
+
+impl IntoBolt for BoltMap {
+    fn into_bolt(self) -> BoltType {
+        BoltType::Map(self)
+    }
+}
+
+impl<T: IntoBolt> IntoBolt for Option<T> {
+    fn into_bolt(self) -> BoltType {
+        match self {
+            Some(value) => value.into_bolt(),
+            None => BoltType::Null(BoltNull),
+        }
+    }
+}
+
+impl<T: IntoBolt> IntoBolt for Vec<T> {
+    fn into_bolt(self) -> BoltType {
+        BoltType::from(
+            self.into_iter()
+                .map(IntoBolt::into_bolt)
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl IntoBolt for &str {
+    fn into_bolt(self) -> BoltType {
+        self.into()
+    }
+}
+
+impl IntoBolt for DateTime<Utc> {
+    fn into_bolt(self) -> BoltType {
+        self.fixed_offset().into()
+    }
+}
+
+/// Types that neo4rs converts directly.
+macro_rules! impl_into_bolt {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoBolt for $ty {
+                fn into_bolt(self) -> BoltType {
+                    self.into()
+                }
+            }
+        )*
+    };
+}
+impl_into_bolt!(
+    String,
+    bool,
+    f32,
+    f64,
+    i8,
+    i16,
+    i32,
+    i64,
+    u16,
+    u32,
+    DateTime<FixedOffset>
+);
+
+/// Integers that neo4rs doesn't convert, which are stored as i64.
+macro_rules! impl_into_bolt_cast {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoBolt for $ty {
+                fn into_bolt(self) -> BoltType {
+                    (self as i64).into()
+                }
+            }
+        )*
+    };
+}
+impl_into_bolt_cast!(u8, u64, u128, i128, usize, isize);
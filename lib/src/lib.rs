@@ -25,19 +25,47 @@
 // #![warn(missing_docs)]
 // #![deny(rustdoc::broken_intra_doc_links)]
 
+mod cypher_query;
 mod entity;
 mod error;
+mod field;
+mod filter;
 mod format;
+mod from_bolt;
+mod into_bolt;
 mod node;
+mod page;
+mod patch;
+mod path;
+mod path_builder;
+mod projection;
+mod query;
+mod related;
 mod relationship;
 mod stamps;
+mod statement;
+mod tracked;
 
 #[cfg(feature = "macros")]
-pub use cypher_dto_macros::{timestamps, Node, Relation};
+pub use cypher_dto_macros::{timestamps, CypherRow, Node, Projection, Relation};
 
+pub use cypher_query::CypherQuery;
 pub use entity::{FieldSet, StampMode};
 pub use error::Error;
-pub use format::{format_param, format_query_fields};
+pub use field::{Expr, Field, FieldValue, OrderBy, Ordered, Predicate};
+pub use filter::{Filter, FilterField};
+pub use format::{escape_identifier, format_param, format_query_fields, format_return_fields};
+pub use from_bolt::FromBolt;
+pub use into_bolt::IntoBolt;
 pub use node::{NodeEntity, NodeId};
-pub use relationship::{RelationBound, RelationEntity, RelationId};
+pub use page::{Cursor, Page, Paged};
+pub use patch::Patch;
+pub use path::TypedPath;
+pub use path_builder::PathBuilder;
+pub use projection::{format_projection, Projection};
+pub use query::{Match, MatchQuery, ReturnItem, Returns, TypedQuery, Var};
+pub use related::{Related, RelatedPath};
+pub use relationship::{Bound, Direction, RelationBound, RelationEntity, RelationId};
 pub use stamps::{Neo4jMap, Stamps};
+pub use statement::Statement;
+pub use tracked::Tracked;
@@ -1,5 +1,17 @@
-use crate::{FieldSet, StampMode};
-use neo4rs::{Node, Query};
+use crate::{
+    entity::{
+        format_merge_sets, format_row_obj, format_row_sets, format_unwind, format_unwind_indexed,
+        rows_param,
+    },
+    escape_identifier,
+    field::{Params, Scope},
+    format::format_labels,
+    patch::format_patch_fields,
+    CypherQuery, Direction, Error, Expr, Field, FieldSet, Filter, FromBolt, Page, Projection,
+    Related, RelatedPath, RelationEntity, StampMode, TypedQuery,
+};
+use neo4rs::{BoltMap, Node, Query, Row};
+use std::{fmt::Display, num::NonZeroUsize, ops::RangeInclusive};
 
 /// A node [Entity].
 pub trait NodeEntity: FieldSet + TryFrom<Node> {
@@ -20,45 +32,483 @@ pub trait NodeEntity: FieldSet + TryFrom<Node> {
     }
 
     fn create(&self) -> Query {
-        let q = Query::new(format!(
+        self.create_query().into()
+    }
+    /// Like [NodeEntity::create], as a [CypherQuery] whose text and parameters can be read.
+    fn create_query(&self) -> CypherQuery {
+        let q = CypherQuery::new(format!(
             "CREATE (n:{})",
             Self::to_query_obj(None, StampMode::Create),
         ));
-        self.add_values_to_params(q, None, StampMode::Create)
+        q.add_values(self, None, StampMode::Create)
+    }
+
+    /// Like [NodeEntity::create], but returns the created node as "n", including values set by the
+    /// database like `created_at`. Decode the row with [NodeEntity::from_returned].
+    fn create_returning(&self) -> Query {
+        self.create_returning_query().into()
+    }
+    /// Like [NodeEntity::create_returning], as a [CypherQuery] whose text and parameters can be read.
+    fn create_returning_query(&self) -> CypherQuery {
+        let mut q = self.create_query();
+        q.text.push_str(" RETURN n");
+        q
+    }
+
+    /// Like [NodeEntity::create], but uses `var` as the variable for the node and the prefix of its parameters,
+    /// and returns it, e.g. `CREATE (p:Person { id: $p_id, ... }) RETURN p`.
+    fn create_as(&self, var: &str) -> Query {
+        self.create_as_query(var).into()
+    }
+    /// Like [NodeEntity::create_as], as a [CypherQuery] whose text and parameters can be read.
+    fn create_as_query(&self, var: &str) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "CREATE ({}:{}) RETURN {}",
+            var,
+            Self::to_query_obj(Some(var), StampMode::Create),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Create)
     }
 
     /// Treats the current values as the desired values and does a merge update (`SET n += ...`).
     ///
-    /// NOTE: Does not support changing the identifier fields.
+    /// NOTE: Does not support changing the identifier fields. Use [NodeEntity::update_with_id] for that.
     fn update(&self) -> Query {
-        let q = Query::new(format!(
+        self.update_query().into()
+    }
+    /// Like [NodeEntity::update], as a [CypherQuery] whose text and parameters can be read.
+    fn update_query(&self) -> CypherQuery {
+        let q = CypherQuery::new(format!(
             "MATCH (n:{}) SET n += {{ {} }}",
             Self::Id::to_query_obj(None, StampMode::Read),
             Self::to_query_fields(None, StampMode::Update),
         ));
-        self.add_values_to_params(q, None, StampMode::Update)
+        q.add_values(self, None, StampMode::Update)
+    }
+
+    /// Like [NodeEntity::update], but returns the updated node as "n", including values set by the
+    /// database like `updated_at`. Decode the row with [NodeEntity::from_returned].
+    fn update_returning(&self) -> Query {
+        self.update_returning_query().into()
+    }
+    /// Like [NodeEntity::update_returning], as a [CypherQuery] whose text and parameters can be read.
+    fn update_returning_query(&self) -> CypherQuery {
+        let mut q = self.update_query();
+        q.text.push_str(" RETURN n");
+        q
+    }
+
+    /// Decodes the row returned by [NodeEntity::create_returning] or [NodeEntity::update_returning].
+    fn from_returned(row: &Row) -> Result<Self, Error>
+    where
+        <Self as TryFrom<Node>>::Error: Display,
+    {
+        let node = row
+            .get::<Node>("n")
+            .map_err(|_e| Error::MissingField("n".to_owned()))?;
+        Self::try_from(node).map_err(|e| Error::FromMapInner(e.to_string()))
+    }
+
+    /// Like [NodeEntity::update], but matches the node by its `old` id, so the id fields can be changed too.
+    ///
+    /// The old id's parameters are prefixed with "old" and the new values' with "n". The updated timestamp is set,
+    /// and the created timestamp is left alone, the same as [StampMode::Merge].
+    ///
+    /// If a uniqueness constraint on the id fields already has a node with the new id, the query fails and nothing is changed.
+    fn update_with_id(&self, old: &Self::Id) -> Query {
+        self.update_with_id_query(old).into()
+    }
+    /// Like [NodeEntity::update_with_id], as a [CypherQuery] whose text and parameters can be read.
+    fn update_with_id_query(&self, old: &Self::Id) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH (n:{}) SET n += {{ {} }}",
+            Self::Id::to_query_obj(Some("old"), StampMode::Read),
+            Self::to_query_fields(Some("n"), StampMode::Merge),
+        ));
+        q.add_values(old, Some("old"), StampMode::Read).add_values(
+            self,
+            Some("n"),
+            StampMode::Merge,
+        )
+    }
+
+    /// Like [NodeEntity::update], but uses `var` as the variable for the node and the prefix of its parameters,
+    /// and returns it.
+    fn update_as(&self, var: &str) -> Query {
+        self.update_as_query(var).into()
+    }
+    /// Like [NodeEntity::update_as], as a [CypherQuery] whose text and parameters can be read.
+    fn update_as_query(&self, var: &str) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH ({}:{}) SET {} += {{ {} }} RETURN {}",
+            var,
+            Self::Id::to_query_obj(Some(var), StampMode::Read),
+            var,
+            Self::to_query_fields(Some(var), StampMode::Update),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Update)
+    }
+
+    /// Creates the node if its id doesn't exist yet, otherwise updates it (`MERGE (n:...) ON CREATE SET ... ON MATCH SET ...`).
+    ///
+    /// Both created and updated timestamps are set on create, and only the updated timestamp on match.
+    ///
+    /// NOTE: Does not support changing the identifier fields.
+    fn merge(&self) -> Query {
+        self.merge_query().into()
+    }
+    /// Like [NodeEntity::merge], as a [CypherQuery] whose text and parameters can be read.
+    fn merge_query(&self) -> CypherQuery {
+        let q = CypherQuery::new(merge_clause::<Self>("n", None));
+        q.add_values(self, None, StampMode::Merge)
+    }
+
+    /// Creates all of the nodes in one query, using "n" as the variable for each node.
+    ///
+    /// With a `chunk_size`, the nodes are created in separate transactions of that many nodes each.
+    /// See [CALL IN TRANSACTIONS](https://neo4j.com/docs/cypher-manual/current/subqueries/subqueries-in-transactions/).
+    fn create_many(items: &[Self], chunk_size: Option<usize>) -> Query {
+        Self::create_many_query(items, chunk_size).into()
+    }
+    /// Like [NodeEntity::create_many], as a [CypherQuery] whose text and parameters can be read.
+    fn create_many_query(items: &[Self], chunk_size: Option<usize>) -> CypherQuery {
+        let body = format!(
+            "CREATE (n:{}) SET {}",
+            format_labels(Self::labels()),
+            format_row_sets::<Self>("n", "row", "=", StampMode::Create),
+        );
+        CypherQuery::new(format_unwind(&body, chunk_size)).param(
+            "rows",
+            rows_param(items.iter().map(|item| item.to_bolt_map(StampMode::Create))),
+        )
+    }
+
+    /// Merges all of the nodes in one query, the same as [NodeEntity::merge] for each one.
+    ///
+    /// `chunk_size` is the same as for [NodeEntity::create_many].
+    fn merge_many(items: &[Self], chunk_size: Option<usize>) -> Query {
+        Self::merge_many_query(items, chunk_size).into()
+    }
+    /// Like [NodeEntity::merge_many], as a [CypherQuery] whose text and parameters can be read.
+    fn merge_many_query(items: &[Self], chunk_size: Option<usize>) -> CypherQuery {
+        let body = format!(
+            "MERGE (n:{}) ON CREATE SET {} ON MATCH SET {}",
+            format_row_obj::<Self::Id>("row"),
+            format_row_sets::<Self>("n", "row", "+=", StampMode::Create),
+            format_row_sets::<Self>("n", "row", "+=", StampMode::Merge),
+        );
+        CypherQuery::new(format_unwind(&body, chunk_size)).param(
+            "rows",
+            rows_param(items.iter().map(|item| item.to_bolt_map(StampMode::Merge))),
+        )
+    }
+
+    /// Reads the nodes that match a [Filter], e.g. `Person::find(&Person::filter().age.gt(30))`.
+    ///
+    /// Uses "n" as the variable for each node.
+    fn find(filter: &Filter<Self>) -> Query {
+        Self::find_query(filter).into()
+    }
+    /// Like [NodeEntity::find], as a [CypherQuery] whose text and parameters can be read.
+    fn find_query(filter: &Filter<Self>) -> CypherQuery {
+        filter.to_query("n", |clause| {
+            format!(
+                "MATCH (n:{}) {} RETURN n",
+                format_labels(Self::labels()),
+                clause
+            )
+        })
+    }
+
+    /// Counts the nodes that match a [Filter], or all of them. The count is returned as "count".
+    fn count(filter: Option<&Filter<Self>>) -> Query {
+        Self::count_query(filter).into()
+    }
+    /// Like [NodeEntity::count], as a [CypherQuery] whose text and parameters can be read.
+    fn count_query(filter: Option<&Filter<Self>>) -> CypherQuery {
+        let mut params = Params::default();
+        let clause = filter
+            .map(|f| format!(" {}", f.render("n", &mut params)))
+            .unwrap_or_default();
+        params.into_query(format!(
+            "MATCH (n:{}){} RETURN count(n) AS count",
+            format_labels(Self::labels()),
+            clause
+        ))
+    }
+
+    /// Counts the nodes in each group with the same value of `field`, optionally filtered.
+    ///
+    /// Groups are ordered from largest to smallest. Returns a single row, which decodes into `(value, count)` pairs.
+    /// Fails if `field` is on another type.
+    fn group_by<V: FromBolt>(
+        field: Field<V>,
+        filter: Option<&Filter<Self>>,
+    ) -> Result<TypedQuery<Vec<(V, i64)>>, Error> {
+        let mut scope = Scope::default();
+        scope.bind(Self::typename(), "n");
+        let mut params = Params::default();
+        let clause = filter
+            .map(|f| format!(" {}", f.render("n", &mut params)))
+            .unwrap_or_default();
+        let value = Expr::from(field).render(&scope, &mut params)?;
+        let text = format!(
+            "MATCH (n:{}){} WITH {} AS value, count(n) AS count ORDER BY count DESC \
+             RETURN collect([value, count]) AS groups",
+            format_labels(Self::labels()),
+            clause,
+            value
+        );
+        Ok(TypedQuery::new(
+            text,
+            params.into_inner(),
+            vec!["groups".to_owned()],
+            |row, columns| FromBolt::from_column(row, &columns[0]),
+        ))
+    }
+
+    /// Reads one [Page] of nodes, using "n" as the variable for each node.
+    ///
+    /// Returns a single row, which decodes into a [Paged] of this type.
+    /// Fails if the page is ordered by a field of another type.
+    fn list(page: &Page) -> Result<Query, Error> {
+        Self::list_query(page).map(Into::into)
+    }
+    /// Like [NodeEntity::list], as a [CypherQuery] whose text and parameters can be read.
+    fn list_query(page: &Page) -> Result<CypherQuery, Error> {
+        let pattern = format!("(n:{})", format_labels(Self::labels()));
+        let (q, params) =
+            page.to_query(&pattern, Self::typename(), "n", Self::Id::field_names())?;
+        Ok(params.into_query(q))
     }
 }
 
+/// Formats a `MERGE` clause for a node, matching on its id fields.
+///
+/// The parameters for it are added with [StampMode::Merge].
+pub(crate) fn merge_clause<T: NodeEntity>(var: &str, prefix: Option<&str>) -> String {
+    format!(
+        "MERGE ({}:{}){}",
+        var,
+        T::Id::to_query_obj(prefix, StampMode::Read),
+        format_merge_sets::<T>(var, prefix)
+    )
+}
+
 /// The identifying fields of a [NodeEntity].
 pub trait NodeId: FieldSet + From<Self::T> + TryFrom<Node> {
     type T: NodeEntity<Id = Self>;
 
     /// Read a [NodeEntity] by its id, using "n" as the variable for the node.
     fn read(&self) -> Query {
-        let q = Query::new(format!(
+        self.read_query().into()
+    }
+    /// Like [NodeId::read], as a [CypherQuery] whose text and parameters can be read.
+    fn read_query(&self) -> CypherQuery {
+        let q = CypherQuery::new(format!(
             "MATCH (n:{}) RETURN n",
             Self::to_query_obj(None, StampMode::Read)
         ));
-        self.add_values_to_params(q, None, StampMode::Read)
+        q.add_values(self, None, StampMode::Read)
+    }
+
+    /// Like [NodeId::read], but uses `var` as the variable for the node, the prefix of its parameters,
+    /// and the column it's returned as, e.g. `MATCH (p:Person { id: $p_id }) RETURN p`.
+    fn read_as(&self, var: &str) -> Query {
+        self.read_as_query(var).into()
+    }
+    /// Like [NodeId::read_as], as a [CypherQuery] whose text and parameters can be read.
+    fn read_as_query(&self, var: &str) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH ({}:{}) RETURN {}",
+            var,
+            Self::to_query_obj(Some(var), StampMode::Read),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Read)
+    }
+
+    /// Like [NodeId::read_as], but returns a [Projection] of the node instead of the whole node,
+    /// e.g. `RETURN p{.id, .name} AS p`. `P` is often [NodeId::T] itself, or its id.
+    fn read_projection<P: Projection>(&self, var: &str) -> Query {
+        self.read_projection_query::<P>(var).into()
+    }
+    /// Like [NodeId::read_projection], as a [CypherQuery] whose text and parameters can be read.
+    fn read_projection_query<P: Projection>(&self, var: &str) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH ({}:{}) RETURN {} AS {}",
+            var,
+            Self::to_query_obj(Some(var), StampMode::Read),
+            P::projection(var),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Read)
+    }
+
+    /// Updates only the properties in `values` (`SET n += { ... }`), using "n" as the variable for the node.
+    /// Null values remove the property.
+    ///
+    /// The updated timestamp is set, and the created timestamp is left alone.
+    /// Usually called by the generated `FooPatch::apply()`.
+    fn patch(&self, values: BoltMap) -> Query {
+        self.patch_query(values).into()
+    }
+    /// Like [NodeId::patch], as a [CypherQuery] whose text and parameters can be read.
+    fn patch_query(&self, values: BoltMap) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH (n:{}) SET n += {{ {} }}",
+            Self::to_query_obj(None, StampMode::Read),
+            format_patch_fields::<Self::T>(&values, None),
+        ));
+        q.add_values(self, None, StampMode::Read)
+            .add_map(values, None)
+    }
+
+    /// Checks whether a node with this id exists. The result is returned as "exists".
+    fn exists(&self) -> Query {
+        self.exists_query().into()
+    }
+    /// Like [NodeId::exists], as a [CypherQuery] whose text and parameters can be read.
+    fn exists_query(&self) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH (n:{}) RETURN count(n) > 0 AS exists",
+            Self::to_query_obj(None, StampMode::Read)
+        ));
+        q.add_values(self, None, StampMode::Read)
+    }
+
+    /// The nodes of type `N` related to this one by relationships of type `R`, along with those relationships.
+    ///
+    /// The relationships can be filtered and the results ordered before calling [Related::build].
+    fn related<R: RelationEntity + FromBolt, N: NodeEntity + FromBolt>(
+        &self,
+        direction: Direction,
+    ) -> Related<R, N> {
+        Related::new(self, direction)
+    }
+
+    /// Like [NodeId::related], but follows `hops` relationships of type `R`, e.g. `1..=3`.
+    fn related_path<R: RelationEntity + FromBolt, N: NodeEntity + FromBolt>(
+        &self,
+        hops: RangeInclusive<usize>,
+    ) -> RelatedPath<R, N> {
+        RelatedPath::new(self, hops)
+    }
+
+    /// Finds a shortest path from this node to `end`, over at most `max_hops` relationships of type `R`
+    /// in either direction.
+    ///
+    /// The path is returned as "p", and decodes into a [TypedPath](crate::TypedPath). Nothing is returned if there is no path,
+    /// or if `end` is this node.
+    fn shortest_path<R: RelationEntity, E: NodeId>(
+        &self,
+        end: &E,
+        max_hops: NonZeroUsize,
+    ) -> Query {
+        self.shortest_path_query::<R, E>(end, max_hops).into()
+    }
+    /// Like [NodeId::shortest_path], as a [CypherQuery] whose text and parameters can be read.
+    fn shortest_path_query<R: RelationEntity, E: NodeId>(
+        &self,
+        end: &E,
+        max_hops: NonZeroUsize,
+    ) -> CypherQuery {
+        self.shortest_path_directed_query::<R, E>(end, max_hops, Direction::Either)
+    }
+    /// Like [NodeId::shortest_path], with every relationship in the given [Direction].
+    fn shortest_path_directed<R: RelationEntity, E: NodeId>(
+        &self,
+        end: &E,
+        max_hops: NonZeroUsize,
+        direction: Direction,
+    ) -> Query {
+        self.shortest_path_directed_query::<R, E>(end, max_hops, direction)
+            .into()
+    }
+    /// Like [NodeId::shortest_path_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn shortest_path_directed_query<R: RelationEntity, E: NodeId>(
+        &self,
+        end: &E,
+        max_hops: NonZeroUsize,
+        direction: Direction,
+    ) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH (s:{}), (e:{}) WHERE s <> e MATCH p = shortestPath((s){}(e)) RETURN p",
+            Self::to_query_obj(Some("s"), StampMode::Read),
+            E::to_query_obj(Some("e"), StampMode::Read),
+            direction.format_pattern(&format!(
+                "[:{}*..{}]",
+                escape_identifier(R::labels()[0]),
+                max_hops
+            )),
+        ));
+        let q = q.add_values(self, Some("s"), StampMode::Read);
+        q.add_values(end, Some("e"), StampMode::Read)
     }
 
     /// Delete a [NodeEntity] by its id, using "n" as the variable for the node.
     fn delete(&self) -> Query {
-        let q = Query::new(format!(
+        self.delete_query().into()
+    }
+    /// Like [NodeId::delete], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_query(&self) -> CypherQuery {
+        let q = CypherQuery::new(format!(
             "MATCH (n:{}) DETACH DELETE n",
             Self::to_query_obj(None, StampMode::Read)
         ));
-        self.add_values_to_params(q, None, StampMode::Read)
+        q.add_values(self, None, StampMode::Read)
+    }
+
+    /// Like [NodeId::delete], but uses `var` as the variable for the node and the prefix of its parameters.
+    fn delete_as(&self, var: &str) -> Query {
+        self.delete_as_query(var).into()
+    }
+    /// Like [NodeId::delete_as], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_as_query(&self, var: &str) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH ({}:{}) DETACH DELETE {}",
+            var,
+            Self::to_query_obj(Some(var), StampMode::Read),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Read)
+    }
+
+    /// Read many [NodeEntity]s by their ids, using "n" as the variable for each node.
+    ///
+    /// The rows are returned in the same order as `ids`. Ids that don't exist are skipped.
+    fn read_many(ids: &[Self]) -> Query {
+        Self::read_many_query(ids).into()
+    }
+    /// Like [NodeId::read_many], as a [CypherQuery] whose text and parameters can be read.
+    fn read_many_query(ids: &[Self]) -> CypherQuery {
+        let body = format!(
+            "MATCH (n:{}) RETURN n ORDER BY i",
+            format_row_obj::<Self>("row")
+        );
+        CypherQuery::new(format_unwind_indexed(&body)).param(
+            "rows",
+            rows_param(ids.iter().map(|id| id.to_bolt_map(StampMode::Read))),
+        )
+    }
+
+    /// Delete many [NodeEntity]s by their ids, using "n" as the variable for each node.
+    ///
+    /// `chunk_size` is the same as for [NodeEntity::create_many].
+    fn delete_many(ids: &[Self], chunk_size: Option<usize>) -> Query {
+        Self::delete_many_query(ids, chunk_size).into()
+    }
+    /// Like [NodeId::delete_many], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_many_query(ids: &[Self], chunk_size: Option<usize>) -> CypherQuery {
+        let body = format!(
+            "MATCH (n:{}) DETACH DELETE n",
+            format_row_obj::<Self>("row")
+        );
+        CypherQuery::new(format_unwind(&body, chunk_size)).param(
+            "rows",
+            rows_param(ids.iter().map(|id| id.to_bolt_map(StampMode::Read))),
+        )
     }
 }
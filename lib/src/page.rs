@@ -0,0 +1,448 @@
+use crate::{
+    escape_identifier,
+    field::{Params, Scope},
+    Error, Expr, FromBolt, OrderBy,
+};
+use chrono::{DateTime, FixedOffset};
+use neo4rs::{BoltNull, BoltType, Row};
+use std::{fmt::Display, str::FromStr};
+
+/// Which page of a listing to read, for [NodeEntity::list](crate::NodeEntity::list) and
+/// [RelationId::list_from](crate::RelationId::list_from).
+///
+/// Results are ordered by the [Page::order_by] fields, then by the id fields, so that the order is stable.
+/// Relationships without id fields are ordered by their `elementId()` instead.
+/// Pages can be selected with [Page::skip], or with a [Cursor] from the previous page ([Page::after]),
+/// which stays correct when items are added or removed in the meantime. Cursors skip items whose ordering
+/// fields are null, so they should be used with non-null fields.
+///
+/// ```ignore
+/// let page = Page::first(20).order_by(Person::fields().age.desc());
+/// let row = graph.execute(Person::list(&page)?).await?.next().await?.unwrap();
+/// let people = Paged::<Person>::try_from(row)?;
+/// if let Some(cursor) = people.next {
+///     let next_page = page.after(cursor);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Page {
+    order_by: Vec<OrderBy>,
+    skip: Option<usize>,
+    limit: Option<usize>,
+    after: Option<Cursor>,
+    total: bool,
+}
+impl Page {
+    /// A page of at most `limit` items.
+    pub fn first(limit: usize) -> Self {
+        Self::default().limit(limit)
+    }
+    /// Orders by a field of the listed type. Can be called more than once.
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order_by.push(order);
+        self
+    }
+    pub fn skip(mut self, skip: usize) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    /// Starts after the item that `cursor` was made from, see [Paged::next].
+    ///
+    /// The cursor must come from a page with the same ordering; otherwise nothing matches.
+    pub fn after(mut self, cursor: Cursor) -> Self {
+        self.after = Some(cursor);
+        self
+    }
+    /// Also counts all of the items, ignoring skip, limit, and cursor. See [Paged::total].
+    pub fn with_total(mut self) -> Self {
+        self.total = true;
+        self
+    }
+
+    /// Formats a listing query.
+    ///
+    /// `pattern` is a `MATCH` pattern that binds `var` to the [typename](crate::FieldSet::typename) `owner`,
+    /// and `id_fields` are the fields used to break ties in the ordering. Without any, `elementId(var)` is used.
+    pub(crate) fn to_query(
+        &self,
+        pattern: &str,
+        owner: &'static str,
+        var: &str,
+        id_fields: &[&str],
+    ) -> Result<(String, Params), Error> {
+        let mut scope = Scope::default();
+        scope.bind(owner, var);
+        // The same fields, read from the last item of the page.
+        let mut last_scope = Scope::default();
+        last_scope.bind(owner, "last(items)");
+        let mut params = Params::default();
+
+        // The ordering, then the id fields that aren't part of it, as (key, descending, the key of the last item).
+        let mut keys: Vec<(String, bool, String)> = self
+            .order_by
+            .iter()
+            .map(|o| {
+                // Only `var` is bound, so a field's own variable can only be that one.
+                let expr = match &o.expr {
+                    Expr::Property {
+                        owner,
+                        name,
+                        var: Some(other),
+                    } => {
+                        if other != var {
+                            return Err(Error::InvalidQuery(format!(
+                                "Can't order a listing of {} by {}.{}.",
+                                var, other, name
+                            )));
+                        }
+                        Expr::Property {
+                            owner,
+                            name,
+                            var: None,
+                        }
+                    }
+                    expr => expr.clone(),
+                };
+                let key = expr.render(&scope, &mut params)?;
+                let last = expr.render(&last_scope, &mut params)?;
+                Ok((key, o.descending, last))
+            })
+            .collect::<Result<_, Error>>()?;
+        for id in id_fields {
+            let key = format!("{}.{}", var, escape_identifier(id));
+            if !keys.iter().any(|(k, _, _)| *k == key) {
+                let last = format!("last(items).{}", escape_identifier(id));
+                keys.push((key, false, last));
+            }
+        }
+        // Something unique has to break ties, or items with the same keys would be skipped or repeated across pages.
+        if id_fields.is_empty() {
+            keys.push((
+                format!("elementId({})", var),
+                false,
+                "elementId(last(items))".to_owned(),
+            ));
+        }
+
+        let mut q = format!("MATCH {}", pattern);
+        if let Some(cursor) = &self.after {
+            q.push_str(&format!(" WHERE {}", keyset(&keys, cursor, &mut params)));
+        }
+        q.push_str(&format!(" WITH {}", var));
+        let order_by = keys
+            .iter()
+            .map(|(k, desc, _)| {
+                if *desc {
+                    format!("{} DESC", k)
+                } else {
+                    k.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        q.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        if let Some(skip) = self.skip {
+            let name = params.add("skip", BoltType::from(skip as i64));
+            q.push_str(&format!(" SKIP ${}", name));
+        }
+        let limit = self
+            .limit
+            .map(|limit| params.add("limit", BoltType::from(limit as i64)));
+        if let Some(limit) = &limit {
+            q.push_str(&format!(" LIMIT ${}", limit));
+        }
+        q.push_str(&format!(" WITH collect({}) AS items", var));
+        let mut returns = vec!["items".to_owned()];
+        if self.total {
+            q.push_str(&format!(
+                " CALL {{ MATCH {} RETURN count({}) AS total }}",
+                pattern, var
+            ));
+            returns.push("total".to_owned());
+        }
+        // A full page has a cursor made from its last item.
+        if let Some(limit) = &limit {
+            let values = keys
+                .iter()
+                .map(|(_, _, last)| last.as_str())
+                .collect::<Vec<_>>();
+            returns.push(format!(
+                "CASE WHEN size(items) = ${} THEN [{}] END AS cursor",
+                limit,
+                values.join(", ")
+            ));
+        }
+        q.push_str(&format!(" RETURN {}", returns.join(", ")));
+        Ok((q, params))
+    }
+}
+
+/// The condition for the rows after `cursor`, e.g. `a > $cursor OR (a = $cursor AND b > $cursor_2)`.
+fn keyset(keys: &[(String, bool, String)], cursor: &Cursor, params: &mut Params) -> String {
+    if keys.len() != cursor.values.len() {
+        return "false".to_owned();
+    }
+    let names: Vec<String> = cursor
+        .values
+        .iter()
+        .map(|v| params.add("cursor", v.clone()))
+        .collect();
+    let mut any = Vec::new();
+    for (i, (key, desc, _)) in keys.iter().enumerate() {
+        let mut all: Vec<String> = keys[..i]
+            .iter()
+            .zip(&names)
+            .map(|((k, _, _), name)| format!("{} = ${}", k, name))
+            .collect();
+        all.push(format!(
+            "{} {} ${}",
+            key,
+            if *desc { "<" } else { ">" },
+            names[i]
+        ));
+        any.push(format!("({})", all.join(" AND ")));
+    }
+    any.join(" OR ")
+}
+
+/// One page of results, decoded from the row returned by a listing query, e.g. [NodeEntity::list](crate::NodeEntity::list).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    /// The number of items in all pages, if requested with [Page::with_total].
+    pub total: Option<i64>,
+    /// Where the next page starts, if this page is full. See [Page::after].
+    pub next: Option<Cursor>,
+}
+impl<T: FromBolt> TryFrom<Row> for Paged<T> {
+    type Error = Error;
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            items: Vec::from_column(&row, "items")?,
+            total: Option::from_column(&row, "total")?,
+            next: Option::<Vec<BoltType>>::from_column(&row, "cursor")?
+                .map(|values| Cursor { values }),
+        })
+    }
+}
+
+/// The position after an item in an ordered listing. See [Page::after].
+///
+/// It is opaque, and can be passed to and from clients as a string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor {
+    values: Vec<BoltType>,
+}
+impl Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for value in &self.values {
+            let encoded = match value {
+                BoltType::Boolean(b) => format!("b{}", b.value as u8),
+                BoltType::Integer(i) => format!("i{};", i.value),
+                BoltType::Float(n) => format!("f{};", n.value.to_bits()),
+                BoltType::String(s) => format!("s{}:{}", s.value.len(), s.value),
+                value => match DateTime::<FixedOffset>::from_bolt(value.clone()) {
+                    Ok(dt) => format!("t{};", dt.to_rfc3339()),
+                    Err(_) => "n".to_owned(),
+                },
+            };
+            for byte in encoded.bytes() {
+                write!(f, "{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+impl FromStr for Cursor {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidCursor(s.to_owned());
+        if !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| s.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        let decoded = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+        let mut values = Vec::new();
+        let mut rest = decoded.as_str();
+        // Splits off the first character, which may be more than one byte in a malformed cursor.
+        fn take_char(rest: &mut &str) -> Option<char> {
+            let c = rest.chars().next()?;
+            *rest = &rest[c.len_utf8()..];
+            Some(c)
+        }
+        // Splits off the text up to `end`, returning it.
+        fn take_until(rest: &mut &str, end: char) -> Option<String> {
+            let (value, tail) = rest.split_once(end)?;
+            *rest = tail;
+            Some(value.to_owned())
+        }
+        while let Some(tag) = take_char(&mut rest) {
+            let value = match tag {
+                'n' => Some(BoltType::Null(BoltNull)),
+                'b' => match take_char(&mut rest) {
+                    Some('0') => Some(BoltType::from(false)),
+                    Some('1') => Some(BoltType::from(true)),
+                    _ => None,
+                },
+                'i' => take_until(&mut rest, ';')
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .map(BoltType::from),
+                'f' => take_until(&mut rest, ';')
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(|bits| BoltType::from(f64::from_bits(bits))),
+                's' => take_until(&mut rest, ':')
+                    .and_then(|len| len.parse::<usize>().ok())
+                    .and_then(|len| {
+                        let value = rest.get(..len)?.to_owned();
+                        rest = &rest[len..];
+                        Some(BoltType::from(value))
+                    }),
+                't' => take_until(&mut rest, ';')
+                    .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+                    .map(BoltType::from),
+                _ => None,
+            };
+            values.push(value.ok_or_else(invalid)?);
+        }
+        Ok(Self { values })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Field;
+    use chrono::TimeZone;
+
+    #[test]
+    fn cursor() {
+        let cursor = Cursor {
+            values: vec![
+                BoltType::from("a:b;c"),
+                BoltType::from(-42),
+                BoltType::from(1.5),
+                BoltType::from(true),
+                BoltType::Null(BoltNull),
+                BoltType::from(
+                    FixedOffset::east_opt(3600)
+                        .unwrap()
+                        .with_ymd_and_hms(2021, 1, 1, 0, 0, 0)
+                        .unwrap(),
+                ),
+            ],
+        };
+        let encoded = cursor.to_string();
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(encoded.parse::<Cursor>().unwrap(), cursor);
+        assert!(matches!(
+            "zz".parse::<Cursor>(),
+            Err(Error::InvalidCursor(_))
+        ));
+        // Truncated, odd-length, signed and non-ASCII input: "s9:a", "s3", "i1", "+f", "é" and "bé".
+        for invalid in [
+            "73393a61", "7333", "6931", "733", "+f", "c3a9", "62c3a9", "é",
+        ] {
+            assert!(
+                matches!(invalid.parse::<Cursor>(), Err(Error::InvalidCursor(_))),
+                "{}",
+                invalid
+            );
+        }
+        // Strings are counted in bytes, so they can hold any text.
+        let cursor = Cursor {
+            values: vec![BoltType::from("é")],
+        };
+        assert_eq!(cursor.to_string().parse::<Cursor>().unwrap(), cursor);
+    }
+
+    #[test]
+    fn list_query() {
+        let age = Field::<Option<u8>>::new("Person", "age");
+        let page = Page::first(10).order_by(age.desc()).with_total();
+        let (q, params) = page.to_query("(n:Person)", "Person", "n", &["id"]).unwrap();
+        assert_eq!(
+            q,
+            "MATCH (n:Person) WITH n ORDER BY n.age DESC, n.id LIMIT $limit \
+             WITH collect(n) AS items CALL { MATCH (n:Person) RETURN count(n) AS total } \
+             RETURN items, total, CASE WHEN size(items) = $limit THEN [last(items).age, last(items).id] END AS cursor"
+        );
+        assert_eq!(params.into_inner().keys().collect::<Vec<_>>(), ["limit"]);
+
+        let cursor = Cursor {
+            values: vec![BoltType::from(30), BoltType::from("abc")],
+        };
+        let (q, params) = page
+            .clone()
+            .after(cursor)
+            .skip(1)
+            .to_query("(n:Person)", "Person", "n", &["id"])
+            .unwrap();
+        assert!(q.starts_with(
+            "MATCH (n:Person) WHERE (n.age < $cursor) OR (n.age = $cursor AND n.id > $cursor_2) \
+             WITH n ORDER BY n.age DESC, n.id SKIP $skip LIMIT $limit"
+        ));
+        assert_eq!(
+            params.into_inner().keys().collect::<Vec<_>>(),
+            ["cursor", "cursor_2", "limit", "skip"]
+        );
+
+        let cursor = Cursor { values: vec![] };
+        let (q, _) = page
+            .after(cursor)
+            .to_query("(n:Person)", "Person", "n", &["id"])
+            .unwrap();
+        assert!(q.starts_with("MATCH (n:Person) WHERE false WITH n"));
+    }
+
+    #[test]
+    fn list_keys() {
+        // The last item's keys are rendered from the field, not by replacing the variable in the text.
+        let name = Field::<String>::new("Person", "name");
+        let page = Page::first(10).order_by(name.clone().of("n").asc());
+        let (q, _) = page.to_query("(n:Person)", "Person", "n", &["id"]).unwrap();
+        assert!(q.ends_with("THEN [last(items).name, last(items).id] END AS cursor"));
+        let page = Page::first(10).order_by(name.of("m").asc());
+        assert!(matches!(
+            page.to_query("(n:Person)", "Person", "n", &["id"]),
+            Err(Error::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn list_without_ids() {
+        // A relationship without id fields is ordered by its element id, so there's always a unique key.
+        let page = Page::first(10);
+        let (q, _) = page
+            .to_query("(n:Person)-[r:KNOWS]->()", "Knows", "r", &[])
+            .unwrap();
+        assert_eq!(
+            q,
+            "MATCH (n:Person)-[r:KNOWS]->() WITH r ORDER BY elementId(r) LIMIT $limit \
+             WITH collect(r) AS items \
+             RETURN items, CASE WHEN size(items) = $limit THEN [elementId(last(items))] END AS cursor"
+        );
+
+        let since = Field::<i64>::new("Knows", "since");
+        let cursor = Cursor {
+            values: vec![BoltType::from(2020), BoltType::from("4:abc:1")],
+        };
+        let (q, _) = page
+            .order_by(since.asc())
+            .after(cursor)
+            .to_query("(n:Person)-[r:KNOWS]->()", "Knows", "r", &[])
+            .unwrap();
+        assert!(q.starts_with(
+            "MATCH (n:Person)-[r:KNOWS]->() \
+             WHERE (r.since > $cursor) OR (r.since = $cursor AND elementId(r) > $cursor_2) \
+             WITH r ORDER BY r.since, elementId(r) LIMIT $limit"
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{format_query_fields, FieldSet, StampMode};
+use neo4rs::BoltMap;
+
+/// An `Option` field of a generated `FooPatch`, which can also set the property to null.
+///
+/// The other fields of a patch are plain `Option`s, where `None` leaves the property alone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Patch<T> {
+    /// Leaves the property alone.
+    #[default]
+    Keep,
+    /// Sets the property to null, which removes it.
+    Null,
+    Set(T),
+}
+impl<T> Patch<T> {
+    pub fn is_keep(&self) -> bool {
+        matches!(self, Patch::Keep)
+    }
+    /// The value of [Patch::Set].
+    pub fn as_set(&self) -> Option<&T> {
+        match self {
+            Patch::Set(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+/// `None` sets the property to null.
+impl<T> From<Option<T>> for Patch<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => Patch::Set(v),
+            None => Patch::Null,
+        }
+    }
+}
+
+/// Formats the fields for `SET var += { ... }` with only the properties in `values`, in name order,
+/// and their parameters prefixed with `prefix`.
+///
+/// The updated timestamp of `T` is set to `datetime()`, and the created timestamp is left alone, the same as [StampMode::Merge].
+pub(crate) fn format_patch_fields<T: FieldSet>(values: &BoltMap, prefix: Option<&str>) -> String {
+    let mut names = values
+        .value
+        .keys()
+        .map(|k| k.value.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    let fields = format_query_fields(names, prefix);
+    let (stamps, _) = T::timestamps();
+    let stamps = stamps.as_query_fields(None, StampMode::Merge);
+    [fields, stamps]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
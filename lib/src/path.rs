@@ -0,0 +1,167 @@
+use crate::{Error, FromBolt};
+use neo4rs::{BoltType, Node, Path, UnboundedRelation};
+use std::fmt::Display;
+
+/// A path of alternating nodes and relationships, decoded from a [Path], e.g. from [NodeId::shortest_path](crate::NodeId::shortest_path).
+///
+/// `N` can be a DTO, or an enum that implements `TryFrom<Node>` by checking the node's labels,
+/// for paths through different types of nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedPath<N, R> {
+    /// The nodes in order, starting with the start node. There is always one more node than relationship.
+    pub nodes: Vec<N>,
+    /// The relationships in order, `relations[i]` being between `nodes[i]` and `nodes[i + 1]`.
+    pub relations: Vec<R>,
+}
+impl<N, R> TypedPath<N, R> {
+    /// The number of relationships.
+    pub fn len(&self) -> usize {
+        self.relations.len()
+    }
+    /// True if the path is a single node.
+    pub fn is_empty(&self) -> bool {
+        self.relations.is_empty()
+    }
+    pub fn start(&self) -> Option<&N> {
+        self.nodes.first()
+    }
+    pub fn end(&self) -> Option<&N> {
+        self.nodes.last()
+    }
+    /// Each relationship with the nodes before and after it.
+    pub fn steps(&self) -> impl Iterator<Item = (&N, &R, &N)> {
+        self.relations
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (&self.nodes[i], r, &self.nodes[i + 1]))
+    }
+}
+impl<N, R> TryFrom<Path> for TypedPath<N, R>
+where
+    N: TryFrom<Node>,
+    N::Error: Display,
+    R: TryFrom<UnboundedRelation>,
+    R::Error: Display,
+{
+    type Error = Error;
+    fn try_from(value: Path) -> Result<Self, Self::Error> {
+        let nodes = value.nodes();
+        let rels = value.rels();
+        let mut path = Self {
+            nodes: Vec::with_capacity(rels.len() + 1),
+            relations: Vec::with_capacity(rels.len()),
+        };
+        // The nodes and relationships are unique, and the indices are (relationship, node) pairs that
+        // walk through them: relationships are 1-based and negative when traversed backwards.
+        let node = |i: usize| -> Result<N, Error> {
+            let node = nodes
+                .get(i)
+                .cloned()
+                .ok_or_else(|| Error::MissingField(format!("node {}", i)))?;
+            N::try_from(node).map_err(|e| Error::FromMapInner(e.to_string()))
+        };
+        path.nodes.push(node(0)?);
+        for step in value.indices().chunks(2) {
+            let (rel, next) = match step {
+                [rel, next] => (rel.unsigned_abs() as usize, *next as usize),
+                _ => return Err(Error::MissingField("path index".to_owned())),
+            };
+            let rel = rel
+                .checked_sub(1)
+                .and_then(|i| rels.get(i))
+                .cloned()
+                .ok_or_else(|| Error::MissingField(format!("relationship {}", rel)))?;
+            path.relations
+                .push(R::try_from(rel).map_err(|e| Error::FromMapInner(e.to_string()))?);
+            path.nodes.push(node(next)?);
+        }
+        Ok(path)
+    }
+}
+impl<N, R> FromBolt for TypedPath<N, R>
+where
+    N: TryFrom<Node>,
+    N::Error: Display,
+    R: TryFrom<UnboundedRelation>,
+    R::Error: Display,
+{
+    fn from_bolt(value: BoltType) -> Result<Self, Error> {
+        Path::from_bolt(value)?.try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neo4rs::{
+        BoltInteger, BoltList, BoltMap, BoltNode, BoltPath, BoltString, BoltUnboundedRelation,
+    };
+
+    /// A node or relationship, decoded as its `name` property.
+    #[derive(Debug, PartialEq)]
+    struct Name(String);
+    impl TryFrom<Node> for Name {
+        type Error = Error;
+        fn try_from(value: Node) -> Result<Self, Error> {
+            value
+                .get("name")
+                .map(Name)
+                .map_err(|_| Error::MissingField("name".to_owned()))
+        }
+    }
+    impl TryFrom<UnboundedRelation> for Name {
+        type Error = Error;
+        fn try_from(value: UnboundedRelation) -> Result<Self, Error> {
+            value
+                .get("name")
+                .map(Name)
+                .map_err(|_| Error::MissingField("name".to_owned()))
+        }
+    }
+
+    fn props(name: &str) -> BoltMap {
+        [(BoltString::from("name"), BoltType::from(name))]
+            .into_iter()
+            .collect()
+    }
+    fn node(id: i64, name: &str) -> BoltType {
+        BoltType::Node(BoltNode::new(
+            BoltInteger::new(id),
+            BoltList::new(),
+            props(name),
+        ))
+    }
+    fn rel(id: i64, name: &str) -> BoltType {
+        BoltType::UnboundedRelation(BoltUnboundedRelation::new(
+            BoltInteger::new(id),
+            BoltString::from("KNOWS"),
+            props(name),
+        ))
+    }
+
+    #[test]
+    fn decode() {
+        // (a)-[x]->(b)<-[y]-(c)
+        let path = BoltType::Path(BoltPath {
+            nodes: BoltList::from(vec![node(1, "a"), node(2, "b"), node(3, "c")]),
+            rels: BoltList::from(vec![rel(10, "x"), rel(11, "y")]),
+            indices: BoltList::from(vec![
+                BoltType::from(1),
+                BoltType::from(1),
+                BoltType::from(-2),
+                BoltType::from(2),
+            ]),
+        });
+        let path = TypedPath::<Name, Name>::from_bolt(path).unwrap();
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.start(), Some(&Name("a".to_owned())));
+        assert_eq!(path.end(), Some(&Name("c".to_owned())));
+        let steps: Vec<_> = path
+            .steps()
+            .map(|(s, r, e)| format!("{}-{}-{}", s.0, r.0, e.0))
+            .collect();
+        assert_eq!(steps, ["a-x-b", "b-y-c"]);
+
+        assert!(TypedPath::<Name, Name>::from_bolt(BoltType::from(1)).is_err());
+    }
+}
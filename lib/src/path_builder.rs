@@ -0,0 +1,89 @@
+use crate::{CypherQuery, NodeEntity, RelationBound, RelationEntity, Statement};
+use neo4rs::Query;
+
+/// Creates a chain of relationships in one query, e.g. `(alice)-[:KNOWS]->(bob)-[:WORKS_AT]->(acme)`.
+///
+/// Nodes use the variables "n0", "n1", etc. and relationships "r0", "r1", etc., which are also their parameter prefixes.
+///
+/// ```ignore
+/// let q = PathBuilder::start(RelationBound::Create(&alice))
+///     .then(&Knows {}, RelationBound::Create(&bob))
+///     .then(&WorksAt {}, RelationBound::Match(&acme_id))
+///     .returning()
+///     .build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathBuilder {
+    /// Allocates the variables, and holds the parameters and the clauses for the nodes.
+    statement: Statement,
+    /// `CREATE` clauses for the relationships.
+    relations: Vec<String>,
+    /// The nodes and relationships, in order.
+    vars: Vec<String>,
+    returning: bool,
+}
+impl PathBuilder {
+    /// Starts a path at a node.
+    pub fn start<T: NodeEntity>(node: RelationBound<T>) -> Self {
+        let mut builder = Self {
+            statement: Statement::new(),
+            relations: Vec::new(),
+            vars: Vec::new(),
+            returning: false,
+        };
+        builder.add_node(node);
+        builder
+    }
+
+    /// Adds a relationship from the last node to a new one.
+    pub fn then<R: RelationEntity, T: NodeEntity>(
+        mut self,
+        rel: &R,
+        end: RelationBound<T>,
+    ) -> Self {
+        let start = self.vars[self.vars.len() - 1].clone();
+        let end_var = format!("n{}", self.relations.len() + 1);
+        let (var, clause) = self.statement.relate_clause(
+            &format!("r{}", self.relations.len()),
+            rel,
+            &start,
+            &end_var,
+        );
+        self.relations.push(clause);
+        self.vars.push(var);
+        self.add_node(end);
+        self
+    }
+
+    /// Returns every node and relationship, by their variables.
+    pub fn returning(mut self) -> Self {
+        self.returning = true;
+        self
+    }
+
+    /// The query as a [Statement], to add more clauses to.
+    pub fn into_statement(self) -> Statement {
+        let mut statement = self.statement;
+        for clause in self.relations {
+            statement.push(clause);
+        }
+        if self.returning {
+            statement.push(format!("RETURN {}", self.vars.join(", ")));
+        }
+        statement
+    }
+
+    pub fn build(self) -> Query {
+        self.into_statement().into_query()
+    }
+    /// Like [PathBuilder::build], as a [CypherQuery] whose text and parameters can be read.
+    pub fn build_query(self) -> CypherQuery {
+        self.into_statement().into_cypher_query()
+    }
+
+    fn add_node<T: NodeEntity>(&mut self, node: RelationBound<T>) {
+        let name = format!("n{}", self.relations.len());
+        let var = self.statement.bound(&name, node);
+        self.vars.push(var);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::FromBolt;
+
+/// A struct that can be read from a Cypher [map projection](https://neo4j.com/docs/cypher-manual/current/values-and-types/maps/#cypher-map-projection),
+/// e.g. `RETURN p{.name, company: c{.name}} AS person`.
+///
+/// Implemented by `#[derive(Node)]` and `#[derive(Relation)]` for their fields,
+/// and by `#[derive(Projection)]` for read-only structs that nest other projections.
+/// The projection is decoded with [FromBolt], so the query and the decoder stay in sync.
+pub trait Projection: FromBolt {
+    /// Formats the map projection for the node or relationship bound to `var`, e.g. `p{.id, .name}`.
+    fn projection(var: &str) -> String;
+}
+
+/// Formats `var{items}`, or `var{.*}` if there are no items.
+pub fn format_projection<I, S>(var: &str, items: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let items = items
+        .into_iter()
+        .map(|item| item.as_ref().to_owned())
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        return format!("{}{{.*}}", var);
+    }
+    format!("{}{{{}}}", var, items.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!(format_projection("p", [".id", ".name"]), "p{.id, .name}");
+        assert_eq!(
+            format_projection("p", [".id", "friends: collect(f{.id})"]),
+            "p{.id, friends: collect(f{.id})}"
+        );
+        assert_eq!(format_projection::<[&str; 0], _>("r", []), "r{.*}");
+    }
+}
@@ -0,0 +1,407 @@
+use crate::{
+    escape_identifier,
+    field::{Params, Scope},
+    format::format_labels,
+    Direction, Error, Expr, Field, FromBolt, NodeEntity, OrderBy, Predicate, RelationEntity,
+};
+use neo4rs::{BoltType, Query, Row};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+/// Starts a typed `MATCH` query.
+///
+/// ```ignore
+/// let fields = Person::fields();
+/// let query = Match::node::<Person>("p")
+///     .rel::<WorksAt>()
+///     .node::<Company>("c")
+///     .where_(fields.age.gt(30))
+///     .order_by(fields.name.asc())
+///     .return_((Var::<Person>::new("p"), Company::fields().name))?;
+/// let mut stream = graph.execute(query.query()).await?;
+/// while let Some(row) = stream.next().await? {
+///     let (person, company_name): (Person, String) = query.decode(&row)?;
+/// }
+/// ```
+pub struct Match;
+impl Match {
+    /// `MATCH (var:Labels)`
+    pub fn node<T: NodeEntity>(var: &str) -> MatchQuery {
+        MatchQuery::default().node::<T>(var)
+    }
+}
+
+/// A `MATCH` query being built, see [Match].
+#[derive(Clone, Debug, Default)]
+pub struct MatchQuery {
+    /// Comma separated patterns, the last of which is being extended.
+    patterns: Vec<String>,
+    /// A relationship that is waiting for its end node: (var, label, direction).
+    rel: Option<(String, &'static str, Direction)>,
+    scope: Scope,
+    predicates: Vec<Predicate>,
+    order_by: Vec<OrderBy>,
+    skip: Option<usize>,
+    limit: Option<usize>,
+    /// The first mistake in building the pattern, returned by [MatchQuery::return_].
+    error: Option<String>,
+}
+impl MatchQuery {
+    /// Adds a node to the pattern.
+    ///
+    /// After a relationship, this is the relationship's end node. Otherwise it starts a new, comma separated, pattern.
+    pub fn node<T: NodeEntity>(mut self, var: &str) -> Self {
+        let node = format!("({}:{})", var, format_labels(T::labels()));
+        match self.rel.take() {
+            Some((rel_var, label, direction)) => {
+                let rel = direction.format_pattern(&format!(
+                    "[{}:{}]",
+                    rel_var,
+                    escape_identifier(label)
+                ));
+                match self.patterns.last_mut() {
+                    Some(pattern) => {
+                        pattern.push_str(&rel);
+                        pattern.push_str(&node);
+                    }
+                    None => self.fail("A relationship must follow a node."),
+                }
+            }
+            None => self.patterns.push(node),
+        }
+        self.scope.bind(T::typename(), var);
+        self
+    }
+    /// Adds a relationship from the previous node, using "r", "r2", etc. as its variable.
+    pub fn rel<R: RelationEntity>(self) -> Self {
+        let mut n = 1;
+        let mut var = "r".to_owned();
+        while self.scope.contains(&var) {
+            n += 1;
+            var = format!("r{}", n);
+        }
+        self.rel_as::<R>(&var)
+    }
+    /// Adds a relationship from the previous node, using `var` as its variable.
+    pub fn rel_as<R: RelationEntity>(mut self, var: &str) -> Self {
+        if self.rel.is_some() {
+            self.fail("A relationship must be followed by a node.");
+        }
+        self.rel = Some((var.to_owned(), R::labels()[0], Direction::Outgoing));
+        self.scope.bind(R::typename(), var);
+        self
+    }
+    /// Sets the direction of the last relationship. The default is [Direction::Outgoing].
+    pub fn direction(mut self, direction: Direction) -> Self {
+        match &mut self.rel {
+            Some((_, _, d)) => *d = direction,
+            None => self.fail("direction() must follow rel()."),
+        }
+        self
+    }
+    /// Adds a `WHERE` condition. Multiple conditions are combined with `AND`.
+    pub fn where_(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.order_by.push(order);
+        self
+    }
+    pub fn skip(mut self, skip: usize) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    /// Keeps the first error, since later ones may follow from it.
+    fn fail(&mut self, error: &str) {
+        self.error.get_or_insert_with(|| error.to_owned());
+    }
+
+    /// Finishes the query, returning `items`: a [ReturnItem] or a tuple of them.
+    ///
+    /// Fails if the pattern is incomplete, or a field's DTO isn't bound to a variable.
+    pub fn return_<R: Returns>(self, items: R) -> Result<TypedQuery<R::Output>, Error> {
+        if self.rel.is_some() {
+            return Err(Error::InvalidQuery(
+                "A relationship must be followed by a node.".to_owned(),
+            ));
+        }
+        if let Some(error) = self.error {
+            return Err(Error::InvalidQuery(error));
+        }
+        let mut params = Params::default();
+        let mut text = format!("MATCH {}", self.patterns.join(", "));
+        if !self.predicates.is_empty() {
+            let predicates = self
+                .predicates
+                .iter()
+                .map(|p| p.render(&self.scope, &mut params))
+                .collect::<Result<Vec<_>, _>>()?;
+            text.push_str(&format!(" WHERE {}", predicates.join(" AND ")));
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut returns = Vec::new();
+        for expr in items.into_exprs() {
+            let rendered = expr.render(&self.scope, &mut params)?;
+            let alias = expr.alias(&self.scope)?;
+            let mut column = alias.clone();
+            let mut n = 1;
+            while columns.contains(&column) {
+                n += 1;
+                column = format!("{}_{}", alias, n);
+            }
+            if rendered == column {
+                returns.push(rendered);
+            } else {
+                returns.push(format!("{} AS {}", rendered, escape_identifier(&column)));
+            }
+            columns.push(column);
+        }
+        text.push_str(&format!(" RETURN {}", returns.join(", ")));
+
+        if !self.order_by.is_empty() {
+            let order_by = self
+                .order_by
+                .iter()
+                .map(|o| o.render(&self.scope, &mut params))
+                .collect::<Result<Vec<_>, _>>()?;
+            text.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        }
+        if let Some(skip) = self.skip {
+            let name = params.add("skip", BoltType::from(skip as i64));
+            text.push_str(&format!(" SKIP ${}", name));
+        }
+        if let Some(limit) = self.limit {
+            let name = params.add("limit", BoltType::from(limit as i64));
+            text.push_str(&format!(" LIMIT ${}", name));
+        }
+
+        Ok(TypedQuery::new(
+            text,
+            params.into_inner(),
+            columns,
+            R::decode,
+        ))
+    }
+}
+
+/// A variable to return whole, e.g. a node as its DTO: `Var::<Person>::new("p")`.
+pub struct Var<T> {
+    var: String,
+    _type: PhantomData<fn() -> T>,
+}
+impl<T> Var<T> {
+    pub fn new(var: &str) -> Self {
+        Self {
+            var: var.to_owned(),
+            _type: PhantomData,
+        }
+    }
+}
+
+/// Something that can be returned from a typed query, and decoded as its [ReturnItem::Output].
+pub trait ReturnItem {
+    type Output: FromBolt;
+    fn into_expr(self) -> Expr;
+}
+impl<T: FromBolt> ReturnItem for Field<T> {
+    type Output = T;
+    fn into_expr(self) -> Expr {
+        self.into()
+    }
+}
+impl<T: FromBolt> ReturnItem for Var<T> {
+    type Output = T;
+    fn into_expr(self) -> Expr {
+        Expr::Var(self.var)
+    }
+}
+
+/// The `RETURN` items of a typed query: a single [ReturnItem], or a tuple of them.
+pub trait Returns {
+    type Output;
+    fn into_exprs(self) -> Vec<Expr>;
+    /// Decodes a row, given the column name of each item.
+    fn decode(row: &Row, columns: &[String]) -> Result<Self::Output, Error>;
+}
+impl<T: ReturnItem> Returns for T {
+    type Output = T::Output;
+    fn into_exprs(self) -> Vec<Expr> {
+        vec![self.into_expr()]
+    }
+    fn decode(row: &Row, columns: &[String]) -> Result<Self::Output, Error> {
+        T::Output::from_column(row, &columns[0])
+    }
+}
+macro_rules! impl_returns_tuple {
+    ($($t:ident $i:tt),*) => {
+        impl<$($t: ReturnItem),*> Returns for ($($t,)*) {
+            type Output = ($($t::Output,)*);
+            fn into_exprs(self) -> Vec<Expr> {
+                vec![$(self.$i.into_expr()),*]
+            }
+            fn decode(row: &Row, columns: &[String]) -> Result<Self::Output, Error> {
+                Ok(($($t::Output::from_column(row, &columns[$i])?,)*))
+            }
+        }
+    };
+}
+impl_returns_tuple!(A 0);
+impl_returns_tuple!(A 0, B 1);
+impl_returns_tuple!(A 0, B 1, C 2);
+impl_returns_tuple!(A 0, B 1, C 2, D 3);
+impl_returns_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_returns_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_returns_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_returns_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// A query built by [MatchQuery::return_], which decodes its rows as `O`.
+pub struct TypedQuery<O> {
+    text: String,
+    params: BTreeMap<String, BoltType>,
+    columns: Vec<String>,
+    decode: fn(&Row, &[String]) -> Result<O, Error>,
+}
+impl<O> std::fmt::Debug for TypedQuery<O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypedQuery")
+            .field("text", &self.text)
+            .field("params", &self.params)
+            .field("columns", &self.columns)
+            .finish()
+    }
+}
+impl<O> TypedQuery<O> {
+    pub(crate) fn new(
+        text: String,
+        params: BTreeMap<String, BoltType>,
+        columns: Vec<String>,
+        decode: fn(&Row, &[String]) -> Result<O, Error>,
+    ) -> Self {
+        Self {
+            text,
+            params,
+            columns,
+            decode,
+        }
+    }
+    /// The Cypher text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// The parameters, by name.
+    pub fn params(&self) -> &BTreeMap<String, BoltType> {
+        &self.params
+    }
+    /// The column name of each returned item.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+    /// A [Query] to run.
+    pub fn query(&self) -> Query {
+        self.params
+            .iter()
+            .fold(Query::new(self.text.clone()), |q, (k, v)| {
+                q.param(k, v.clone())
+            })
+    }
+    /// Decodes a row returned by [TypedQuery::query].
+    pub fn decode(&self, row: &Row) -> Result<O, Error> {
+        (self.decode)(row, &self.columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::tests::{Bar, Baz, Foo};
+    use chrono::{DateTime, FixedOffset, Utc};
+    use neo4rs::{BoltList, BoltString};
+
+    #[test]
+    fn match_query() {
+        let age = Field::<u8>::new("Foo", "age");
+        let name = Field::<String>::new("Foo", "name");
+        let created = Field::<DateTime<Utc>>::new("Bar", "created");
+        let query = Match::node::<Foo>("p")
+            .rel::<Baz>()
+            .node::<Bar>("c")
+            .rel::<Baz>()
+            .direction(Direction::Incoming)
+            .node::<Foo>("q")
+            .where_(age.clone().gt(30))
+            .where_(age.clone().of("q").lt(30))
+            .order_by(age.clone().desc())
+            .skip(10)
+            .limit(5)
+            .return_((name.clone(), created, name.of("q"), age))
+            .unwrap();
+        assert_eq!(
+            query.text(),
+            "MATCH (p:Foo:FooLabel2)-[r:BAZ]->(c:Bar)<-[r2:BAZ]-(q:Foo:FooLabel2) \
+             WHERE p.age > $p_age AND q.age < $q_age \
+             RETURN p.name AS p_name, c.created AS c_created, q.name AS q_name, p.age AS p_age \
+             ORDER BY p.age DESC SKIP $skip LIMIT $limit"
+        );
+        assert_eq!(
+            query.params().keys().collect::<Vec<_>>(),
+            ["limit", "p_age", "q_age", "skip"]
+        );
+
+        let now = DateTime::<FixedOffset>::parse_from_rfc3339("2023-01-02T03:04:05Z").unwrap();
+        let row = Row::new(
+            BoltList::from(
+                query
+                    .columns()
+                    .iter()
+                    .map(|c| BoltType::String(BoltString::from(c.as_str())))
+                    .collect::<Vec<_>>(),
+            ),
+            BoltList::from(vec![
+                BoltType::from("Alice"),
+                BoltType::from(now),
+                BoltType::from("Bob"),
+                BoltType::from(42),
+            ]),
+        );
+        let (name, created, other, age) = query.decode(&row).unwrap();
+        assert_eq!(name, "Alice");
+        assert_eq!(created, now);
+        assert_eq!(other, "Bob");
+        assert_eq!(age, 42);
+    }
+
+    #[test]
+    fn return_single() {
+        let query = Match::node::<Foo>("p")
+            .node::<Bar>("c")
+            .return_(Var::<BoltType>::new("p"))
+            .unwrap();
+        assert_eq!(query.text(), "MATCH (p:Foo:FooLabel2), (c:Bar) RETURN p");
+        assert_eq!(query.columns(), ["p"]);
+    }
+
+    #[test]
+    fn invalid() {
+        let name = Field::<String>::new("Foo", "name");
+        let unbound = Match::node::<Bar>("b").return_(name);
+        assert!(matches!(unbound, Err(Error::InvalidQuery(_))));
+        let dangling = Match::node::<Foo>("f")
+            .rel::<Baz>()
+            .return_(Var::<BoltType>::new("f"));
+        assert!(matches!(dangling, Err(Error::InvalidQuery(_))));
+        let direction = Match::node::<Foo>("f")
+            .direction(Direction::Incoming)
+            .return_(Var::<BoltType>::new("f"));
+        assert!(matches!(direction, Err(Error::InvalidQuery(_))));
+        let no_start = MatchQuery::default()
+            .rel::<Baz>()
+            .node::<Foo>("f")
+            .return_(Var::<BoltType>::new("f"));
+        assert!(matches!(no_start, Err(Error::InvalidQuery(_))));
+    }
+}
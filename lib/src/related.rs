@@ -0,0 +1,181 @@
+use crate::{
+    escape_identifier,
+    field::{Params, Scope},
+    format::format_labels,
+    format_param, Direction, Error, Expr, Filter, FromBolt, NodeEntity, NodeId, OrderBy, Predicate,
+    RelationEntity, StampMode, TypedQuery,
+};
+use std::{marker::PhantomData, ops::RangeInclusive};
+
+/// The nodes related to a node, and the relationships to them. See [NodeId::related].
+///
+/// Uses "n" as the variable for the start node, "r" for the relationship, and "m" for the related node.
+pub struct Related<R, N> {
+    traversal: Traversal,
+    _types: PhantomData<fn() -> (R, N)>,
+}
+impl<R: RelationEntity + FromBolt, N: NodeEntity + FromBolt> Related<R, N> {
+    pub(crate) fn new<T: NodeId>(from: &T, direction: Direction) -> Self {
+        Self {
+            traversal: Traversal::new::<T, R, N>(from, direction),
+            _types: PhantomData,
+        }
+    }
+    /// Only follows relationships that match `filter`. Can be called more than once.
+    pub fn filter(mut self, filter: Filter<R>) -> Self {
+        self.traversal.filters.push(filter.into());
+        self
+    }
+    /// Orders by a field of the relationship or the related node.
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.traversal.order_by.push(order);
+        self
+    }
+    /// The query, which returns a single row that decodes into `(relationship, node)` pairs.
+    ///
+    /// Fails if the order is on a field of another type.
+    pub fn build(self) -> Result<TypedQuery<Vec<(R, N)>>, Error> {
+        self.traversal.build(None)
+    }
+}
+
+/// The nodes reachable from a node over one or more relationships of the same type. See [NodeId::related_path].
+///
+/// Uses "n" as the variable for the start node, "rs" for the list of relationships, and "m" for the node at the end.
+pub struct RelatedPath<R, N> {
+    traversal: Traversal,
+    hops: RangeInclusive<usize>,
+    _types: PhantomData<fn() -> (R, N)>,
+}
+impl<R: RelationEntity + FromBolt, N: NodeEntity + FromBolt> RelatedPath<R, N> {
+    pub(crate) fn new<T: NodeId>(from: &T, hops: RangeInclusive<usize>) -> Self {
+        Self {
+            traversal: Traversal::new::<T, R, N>(from, Direction::Outgoing),
+            hops,
+            _types: PhantomData,
+        }
+    }
+    /// The direction of every relationship. The default is [Direction::Outgoing].
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.traversal.direction = direction;
+        self
+    }
+    /// Only follows relationships that match `filter`. Can be called more than once.
+    pub fn filter(mut self, filter: Filter<R>) -> Self {
+        self.traversal.filters.push(filter.into());
+        self
+    }
+    /// Orders by a field of the node at the end.
+    pub fn order_by(mut self, order: OrderBy) -> Self {
+        self.traversal.order_by.push(order);
+        self
+    }
+    /// The query, which returns a single row that decodes into `(relationships, node)` pairs, one for each path.
+    ///
+    /// Fails if the order is on a field of the relationships, since each path has a list of them,
+    /// or on a field of another type.
+    #[allow(clippy::type_complexity)]
+    pub fn build(self) -> Result<TypedQuery<Vec<(Vec<R>, N)>>, Error> {
+        for order in &self.traversal.order_by {
+            if let Expr::Property { owner, name, .. } = &order.expr {
+                if *owner == R::typename() {
+                    return Err(Error::InvalidQuery(format!(
+                        "Can't order a path by the relationship field {}.{}, only by fields of {}.",
+                        owner,
+                        name,
+                        N::typename()
+                    )));
+                }
+            }
+        }
+        self.traversal.build(Some(self.hops))
+    }
+}
+
+/// What [Related] and [RelatedPath] have in common.
+struct Traversal {
+    start: String,
+    rel_typename: &'static str,
+    rel_label: &'static str,
+    node_typename: &'static str,
+    node_labels: String,
+    params: Params,
+    direction: Direction,
+    filters: Vec<Predicate>,
+    order_by: Vec<OrderBy>,
+}
+impl Traversal {
+    fn new<T: NodeId, R: RelationEntity, N: NodeEntity>(from: &T, direction: Direction) -> Self {
+        let mut params = Params::default();
+        for (key, value) in from.to_bolt_map(StampMode::Read).value {
+            params.add(&format_param(&key.value, Some("n")), value);
+        }
+        Self {
+            start: format!("(n:{})", T::to_query_obj(Some("n"), StampMode::Read)),
+            rel_typename: R::typename(),
+            rel_label: R::labels()[0],
+            node_typename: N::typename(),
+            node_labels: format_labels(N::labels()),
+            params,
+            direction,
+            filters: Vec::new(),
+            order_by: Vec::new(),
+        }
+    }
+
+    /// Formats the query. With `hops`, the relationships are a list named "rs".
+    fn build<O: FromBolt>(
+        self,
+        hops: Option<RangeInclusive<usize>>,
+    ) -> Result<TypedQuery<O>, Error> {
+        let mut params = self.params;
+        let mut scope = Scope::default();
+        scope.bind(self.rel_typename, "r");
+        scope.bind(self.node_typename, "m");
+
+        let rel = match &hops {
+            Some(hops) => format!(
+                "[rs:{}*{}..{}]",
+                escape_identifier(self.rel_label),
+                hops.start(),
+                hops.end()
+            ),
+            None => format!("[r:{}]", escape_identifier(self.rel_label)),
+        };
+        let mut q = format!(
+            "MATCH {}{}(m:{})",
+            self.start,
+            self.direction.format_pattern(&rel),
+            self.node_labels
+        );
+        if !self.filters.is_empty() {
+            let filters = self
+                .filters
+                .iter()
+                .map(|f| f.render(&scope, &mut params))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" AND ");
+            match hops {
+                Some(_) => q.push_str(&format!(" WHERE ALL(r IN rs WHERE {})", filters)),
+                None => q.push_str(&format!(" WHERE {}", filters)),
+            }
+        }
+        let rel_var = if hops.is_some() { "rs" } else { "r" };
+        q.push_str(&format!(" WITH {}, m", rel_var));
+        if !self.order_by.is_empty() {
+            let order_by = self
+                .order_by
+                .iter()
+                .map(|o| o.render(&scope, &mut params))
+                .collect::<Result<Vec<_>, _>>()?;
+            q.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        }
+        q.push_str(&format!(" RETURN collect([{}, m]) AS related", rel_var));
+        Ok(TypedQuery::new(
+            q,
+            params.into_inner(),
+            vec!["related".to_owned()],
+            |row, columns| FromBolt::from_column(row, &columns[0]),
+        ))
+    }
+}
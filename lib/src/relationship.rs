@@ -1,5 +1,15 @@
-use crate::{FieldSet, NodeEntity, NodeId, StampMode};
-use neo4rs::{Query, Relation, UnboundedRelation};
+use crate::{
+    entity::{
+        format_merge_sets, format_row_obj, format_row_sets, format_unwind, format_unwind_indexed,
+        rows_param,
+    },
+    format::format_labels,
+    node::merge_clause,
+    patch::format_patch_fields,
+    CypherQuery, Error, FieldSet, NodeEntity, NodeId, Page, Projection, StampMode,
+};
+use neo4rs::{BoltMap, BoltType, Node, Query, Relation, Row, UnboundedRelation};
+use std::fmt::Display;
 
 /// A relationship entity.
 pub trait RelationEntity: FieldSet + TryFrom<Relation> + TryFrom<UnboundedRelation> {
@@ -24,21 +34,205 @@ pub trait RelationEntity: FieldSet + TryFrom<Relation> + TryFrom<UnboundedRelati
         start: RelationBound<S>,
         end: RelationBound<E>,
     ) -> Query {
+        self.create_query::<S, E>(start, end).into()
+    }
+    /// Like [RelationEntity::create], as a [CypherQuery] whose text and parameters can be read.
+    fn create_query<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+    ) -> CypherQuery {
         let q = format!(
             r###"
           {}
           {}
-          CREATE (s)-[:{}]->(e)
+          CREATE (s)-[r:{}]->(e)
           "###,
             start.to_query_clause("s"),
             end.to_query_clause("e"),
             Self::to_query_obj(None, StampMode::Create)
         );
         // trace!("creating relation: {}", q);
-        let mut q = Query::new(q);
+        let mut q = CypherQuery::new(q);
+        q = start.add_params(q, "s");
+        q = end.add_params(q, "e");
+        q.add_values(self, None, StampMode::Create)
+    }
+
+    /// Like [RelationEntity::create], but returns the start node, the created relationship, and the end node
+    /// as "s", "r", and "e". Decode the row with [RelationEntity::from_returned].
+    fn create_returning<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+    ) -> Query {
+        self.create_returning_query::<S, E>(start, end).into()
+    }
+    /// Like [RelationEntity::create_returning], as a [CypherQuery] whose text and parameters can be read.
+    fn create_returning_query<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+    ) -> CypherQuery {
+        let mut q = self.create_query(start, end);
+        q.text.push_str(" RETURN s, r, e");
+        q
+    }
+
+    /// Decodes the row returned by [RelationEntity::create_returning], e.g. into `Bound<S, Self, E>` or
+    /// `Bound<S::Id, Self, E::Id>`.
+    fn from_returned<S, E>(row: &Row) -> Result<Bound<S, Self, E>, Error>
+    where
+        <Self as TryFrom<Relation>>::Error: Display,
+        S: TryFrom<Node>,
+        S::Error: Display,
+        E: TryFrom<Node>,
+        E::Error: Display,
+    {
+        Bound::from_row_as(row, ("s", "r", "e"))
+    }
+
+    /// Like [RelationEntity::create], but uses `vars` as the `(start, relationship, end)` variables and the
+    /// prefixes of their parameters, and returns all three. The row can be decoded with [Bound::from_row_as].
+    fn create_as<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+        vars: (&str, &str, &str),
+    ) -> Query {
+        self.create_as_query::<S, E>(start, end, vars).into()
+    }
+    /// Like [RelationEntity::create_as], as a [CypherQuery] whose text and parameters can be read.
+    fn create_as_query<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+        vars: (&str, &str, &str),
+    ) -> CypherQuery {
+        let (s, r, e) = vars;
+        let q = format!(
+            r###"
+          {}
+          {}
+          CREATE ({})-[{}:{}]->({})
+          RETURN {}, {}, {}
+          "###,
+            start.to_query_clause(s),
+            end.to_query_clause(e),
+            s,
+            r,
+            Self::to_query_obj(Some(r), StampMode::Create),
+            e,
+            s,
+            r,
+            e
+        );
+        let mut q = CypherQuery::new(q);
+        q = start.add_params(q, s);
+        q = end.add_params(q, e);
+        q.add_values(self, Some(r), StampMode::Create)
+    }
+
+    /// Like [RelationEntity::create], but uses `MERGE` so that running it again doesn't create a duplicate.
+    ///
+    /// The relationship is matched on its [RelationId] fields, or on its type alone if it has none.
+    /// Its other fields are set on both create and match, with timestamps handled per [StampMode::Merge].
+    fn merge<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+    ) -> Query {
+        self.merge_query::<S, E>(start, end).into()
+    }
+    /// Like [RelationEntity::merge], as a [CypherQuery] whose text and parameters can be read.
+    fn merge_query<S: NodeEntity, E: NodeEntity>(
+        &self,
+        start: RelationBound<S>,
+        end: RelationBound<E>,
+    ) -> CypherQuery {
+        let q = format!(
+            r###"
+          {}
+          {}
+          MERGE (s)-[r:{}]->(e){}
+          "###,
+            start.to_query_clause("s"),
+            end.to_query_clause("e"),
+            Self::Id::to_query_obj(None, StampMode::Read),
+            format_merge_sets::<Self>("r", None),
+        );
+        let mut q = CypherQuery::new(q);
         q = start.add_params(q, "s");
         q = end.add_params(q, "e");
-        self.add_values_to_params(q, None, StampMode::Create)
+        q.add_values(self, None, StampMode::Merge)
+    }
+
+    /// Creates all of the relationships in one query, between existing start and end nodes.
+    ///
+    /// Each item is a `(start, relationship, end)` tuple. Uses "s", "r", and "e" as the variables.
+    ///
+    /// `chunk_size` is the same as for [NodeEntity::create_many].
+    fn create_many<S: NodeId, E: NodeId>(
+        items: &[(S, Self, E)],
+        chunk_size: Option<usize>,
+    ) -> Query {
+        Self::create_many_query::<S, E>(items, chunk_size).into()
+    }
+    /// Like [RelationEntity::create_many], as a [CypherQuery] whose text and parameters can be read.
+    fn create_many_query<S: NodeId, E: NodeId>(
+        items: &[(S, Self, E)],
+        chunk_size: Option<usize>,
+    ) -> CypherQuery {
+        let body = format!(
+            "MATCH (s:{}) MATCH (e:{}) CREATE (s)-[r:{}]->(e) SET {}",
+            format_row_obj::<S>("row.s"),
+            format_row_obj::<E>("row.e"),
+            format_labels(Self::labels()),
+            format_row_sets::<Self>("r", "row.r", "=", StampMode::Create),
+        );
+        CypherQuery::new(format_unwind(&body, chunk_size)).param(
+            "rows",
+            rows_param(
+                items
+                    .iter()
+                    .map(|(s, r, e)| bound_row(s, r.to_bolt_map(StampMode::Create), e)),
+            ),
+        )
+    }
+
+    /// Merges all of the relationships in one query, the same as [RelationEntity::merge] for each one,
+    /// between existing start and end nodes.
+    ///
+    /// Each item is a `(start, relationship, end)` tuple. Uses "s", "r", and "e" as the variables.
+    ///
+    /// `chunk_size` is the same as for [NodeEntity::create_many].
+    fn merge_many<S: NodeId, E: NodeId>(
+        items: &[(S, Self, E)],
+        chunk_size: Option<usize>,
+    ) -> Query {
+        Self::merge_many_query::<S, E>(items, chunk_size).into()
+    }
+    /// Like [RelationEntity::merge_many], as a [CypherQuery] whose text and parameters can be read.
+    fn merge_many_query<S: NodeId, E: NodeId>(
+        items: &[(S, Self, E)],
+        chunk_size: Option<usize>,
+    ) -> CypherQuery {
+        let body = format!(
+            "MATCH (s:{}) MATCH (e:{}) MERGE (s)-[r:{}]->(e) ON CREATE SET {} ON MATCH SET {}",
+            format_row_obj::<S>("row.s"),
+            format_row_obj::<E>("row.e"),
+            format_row_obj::<Self::Id>("row.r"),
+            format_row_sets::<Self>("r", "row.r", "+=", StampMode::Create),
+            format_row_sets::<Self>("r", "row.r", "+=", StampMode::Merge),
+        );
+        CypherQuery::new(format_unwind(&body, chunk_size)).param(
+            "rows",
+            rows_param(
+                items
+                    .iter()
+                    .map(|(s, r, e)| bound_row(s, r.to_bolt_map(StampMode::Merge), e)),
+            ),
+        )
     }
 
     /// Use only for relations that have one or more ID fields, otherwise use the other `update_` methods.
@@ -47,48 +241,121 @@ pub trait RelationEntity: FieldSet + TryFrom<Relation> + TryFrom<UnboundedRelati
     ///
     /// Treats the current values as the desired values and does a merge update (`SET r += ...`).
     ///
-    /// NOTE: Does not support changing the identifier fields.
+    /// NOTE: Does not support changing the identifier fields. Use [RelationEntity::update_with_id] for that.
     fn update(&self) -> Query {
+        self.update_query().into()
+    }
+    /// Like [RelationEntity::update], as a [CypherQuery] whose text and parameters can be read.
+    fn update_query(&self) -> CypherQuery {
         assert!(!Self::Id::field_names().is_empty());
-        let q = Query::new(format!(
-            "MATCH ()-[r:{}]-()
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[r:{}]->()
              SET r += {{ {} }}",
             Self::Id::to_query_obj(None, StampMode::Read),
             Self::to_query_fields(None, StampMode::Update),
         ));
-        self.add_values_to_params(q, None, StampMode::Update)
+        q.add_values(self, None, StampMode::Update)
+    }
+
+    /// Like [RelationEntity::update], but matches the relationship by its `old` id, so the id fields can be changed too.
+    ///
+    /// The old id's parameters are prefixed with "old" and the new values' with "r". The updated timestamp is set,
+    /// and the created timestamp is left alone, the same as [StampMode::Merge].
+    fn update_with_id(&self, old: &Self::Id) -> Query {
+        self.update_with_id_query(old).into()
+    }
+    /// Like [RelationEntity::update_with_id], as a [CypherQuery] whose text and parameters can be read.
+    fn update_with_id_query(&self, old: &Self::Id) -> CypherQuery {
+        assert!(!Self::Id::field_names().is_empty());
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[r:{}]->() SET r += {{ {} }}",
+            Self::Id::to_query_obj(Some("old"), StampMode::Read),
+            Self::to_query_fields(Some("r"), StampMode::Merge),
+        ));
+        q.add_values(old, Some("old"), StampMode::Read).add_values(
+            self,
+            Some("r"),
+            StampMode::Merge,
+        )
     }
 
     /// Treats the current values as the desired values and does a merge update (`SET r += ...`).
     ///
+    /// Matches relationships going out of `from`, the same direction used by [RelationEntity::create].
+    ///
     /// NOTE: Does not support changing the identifier fields.
     fn update_from<T: NodeId>(&self, from: &T) -> Query {
-        let mut q = Query::new(format!(
-            "MATCH (n:{})-[r:{}]-()
+        self.update_from_query::<T>(from).into()
+    }
+    /// Like [RelationEntity::update_from], as a [CypherQuery] whose text and parameters can be read.
+    fn update_from_query<T: NodeId>(&self, from: &T) -> CypherQuery {
+        self.update_from_directed_query(from, Direction::Outgoing)
+    }
+
+    /// Same as [RelationEntity::update_from], but for relationships in the given [Direction] from `from`.
+    fn update_from_directed<T: NodeId>(&self, from: &T, direction: Direction) -> Query {
+        self.update_from_directed_query::<T>(from, direction).into()
+    }
+    /// Like [RelationEntity::update_from_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn update_from_directed_query<T: NodeId>(&self, from: &T, direction: Direction) -> CypherQuery {
+        let mut q = CypherQuery::new(format!(
+            "MATCH (n:{}){}()
              SET r += {{ {} }}",
             T::to_query_obj(Some("n"), StampMode::Read),
-            Self::Id::to_query_obj(None, StampMode::Read),
+            direction.format_pattern(&format!(
+                "[r:{}]",
+                Self::Id::to_query_obj(None, StampMode::Read)
+            )),
             Self::to_query_fields(None, StampMode::Update),
         ));
-        q = from.add_values_to_params(q, Some("n"), StampMode::Read);
-        self.add_values_to_params(q, None, StampMode::Update)
+        q = q.add_values(from, Some("n"), StampMode::Read);
+        q.add_values(self, None, StampMode::Update)
     }
 
     /// Treats the current values as the desired values and does a merge update (`SET r += ...`).
     ///
+    /// Matches relationships going from `start` to `end`, the same direction used by [RelationEntity::create].
+    ///
     /// NOTE: Does not support changing the identifier fields.
     fn update_between<S: NodeId, E: NodeId>(&self, start: &S, end: &E) -> Query {
-        let mut q = Query::new(format!(
-            "MATCH (s:{})-[r:{}]-(e:{})
+        self.update_between_query::<S, E>(start, end).into()
+    }
+    /// Like [RelationEntity::update_between], as a [CypherQuery] whose text and parameters can be read.
+    fn update_between_query<S: NodeId, E: NodeId>(&self, start: &S, end: &E) -> CypherQuery {
+        self.update_between_directed_query(start, end, Direction::Outgoing)
+    }
+
+    /// Same as [RelationEntity::update_between], but for relationships in the given [Direction] from `start`.
+    fn update_between_directed<S: NodeId, E: NodeId>(
+        &self,
+        start: &S,
+        end: &E,
+        direction: Direction,
+    ) -> Query {
+        self.update_between_directed_query::<S, E>(start, end, direction)
+            .into()
+    }
+    /// Like [RelationEntity::update_between_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn update_between_directed_query<S: NodeId, E: NodeId>(
+        &self,
+        start: &S,
+        end: &E,
+        direction: Direction,
+    ) -> CypherQuery {
+        let mut q = CypherQuery::new(format!(
+            "MATCH (s:{}){}(e:{})
              SET r += {{ {} }}",
             S::to_query_obj(Some("s"), StampMode::Read),
-            Self::Id::to_query_obj(None, StampMode::Read),
+            direction.format_pattern(&format!(
+                "[r:{}]",
+                Self::Id::to_query_obj(None, StampMode::Read)
+            )),
             E::to_query_obj(Some("e"), StampMode::Read),
             Self::to_query_fields(None, StampMode::Update),
         ));
-        q = start.add_values_to_params(q, Some("s"), StampMode::Read);
-        q = end.add_values_to_params(q, Some("e"), StampMode::Read);
-        self.add_values_to_params(q, None, StampMode::Update)
+        q = q.add_values(start, Some("s"), StampMode::Read);
+        q = q.add_values(end, Some("e"), StampMode::Read);
+        q.add_values(self, None, StampMode::Update)
     }
 }
 
@@ -102,84 +369,377 @@ pub trait RelationId:
     ///
     /// This will read all relations of the same type if [FieldSet::field_names()] is empty.
     fn read(&self) -> Query {
+        self.read_query().into()
+    }
+    /// Like [RelationId::read], as a [CypherQuery] whose text and parameters can be read.
+    fn read_query(&self) -> CypherQuery {
         assert!(!Self::field_names().is_empty());
-        let q = Query::new(format!(
-            "MATCH [r:{}] RETURN r",
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[r:{}]->() RETURN r",
             Self::to_query_obj(None, StampMode::Read)
         ));
-        self.add_values_to_params(q, None, StampMode::Read)
+        q.add_values(self, None, StampMode::Read)
+    }
+    /// Like [RelationId::read], but uses `var` as the variable for the relationship, the prefix of its parameters,
+    /// and the column it's returned as.
+    fn read_as(&self, var: &str) -> Query {
+        self.read_as_query(var).into()
+    }
+    /// Like [RelationId::read_as], as a [CypherQuery] whose text and parameters can be read.
+    fn read_as_query(&self, var: &str) -> CypherQuery {
+        assert!(!Self::field_names().is_empty());
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[{}:{}]->() RETURN {}",
+            var,
+            Self::to_query_obj(Some(var), StampMode::Read),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Read)
+    }
+    /// Like [RelationId::read_as], but returns a [Projection] of the relationship instead of the whole relationship,
+    /// e.g. `RETURN r{.since} AS r`.
+    fn read_projection<P: Projection>(&self, var: &str) -> Query {
+        self.read_projection_query::<P>(var).into()
+    }
+    /// Like [RelationId::read_projection], as a [CypherQuery] whose text and parameters can be read.
+    fn read_projection_query<P: Projection>(&self, var: &str) -> CypherQuery {
+        assert!(!Self::field_names().is_empty());
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[{}:{}]->() RETURN {} AS {}",
+            var,
+            Self::to_query_obj(Some(var), StampMode::Read),
+            P::projection(var),
+            var
+        ));
+        q.add_values(self, Some(var), StampMode::Read)
+    }
+    /// Updates only the properties in `values` (`SET r += { ... }`). Null values remove the property.
+    ///
+    /// Use only for relations that have one or more ID fields, the same as [RelationId::read].
+    /// Usually called by the generated `FooPatch::apply()`.
+    fn patch(&self, values: BoltMap) -> Query {
+        self.patch_query(values).into()
+    }
+    /// Like [RelationId::patch], as a [CypherQuery] whose text and parameters can be read.
+    fn patch_query(&self, values: BoltMap) -> CypherQuery {
+        assert!(!Self::field_names().is_empty());
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[r:{}]->() SET r += {{ {} }}",
+            Self::to_query_obj(None, StampMode::Read),
+            format_patch_fields::<Self::T>(&values, None),
+        ));
+        q.add_values(self, None, StampMode::Read)
+            .add_map(values, None)
     }
-    /// Reads relationship(s) connected to a specific node.
+    /// Reads relationship(s) along with their start and end nodes, using "s", "r", and "e" as the variables.
+    ///
+    /// The result rows can be decoded with [Bound], e.g. `Bound<S, Self::T, E>` or `Bound<S::Id, Self::T, E::Id>`.
+    ///
+    /// This will read all relations of the same type between `S` and `E` nodes if [FieldSet::field_names()] is empty.
+    fn read_bound<S: NodeEntity, E: NodeEntity>(&self) -> Query {
+        self.read_bound_query::<S, E>().into()
+    }
+    /// Like [RelationId::read_bound], as a [CypherQuery] whose text and parameters can be read.
+    fn read_bound_query<S: NodeEntity, E: NodeEntity>(&self) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH (s:{})-[r:{}]->(e:{})
+             RETURN s, r, e",
+            format_labels(S::labels()),
+            Self::to_query_obj(None, StampMode::Read),
+            format_labels(E::labels()),
+        ));
+        q.add_values(self, None, StampMode::Read)
+    }
+    /// Like [RelationId::read_bound], but uses `vars` as the `(start, relationship, end)` variables, and the
+    /// relationship's variable as the prefix of its parameters. The rows can be decoded with [Bound::from_row_as].
+    fn read_bound_as<S: NodeEntity, E: NodeEntity>(&self, vars: (&str, &str, &str)) -> Query {
+        self.read_bound_as_query::<S, E>(vars).into()
+    }
+    /// Like [RelationId::read_bound_as], as a [CypherQuery] whose text and parameters can be read.
+    fn read_bound_as_query<S: NodeEntity, E: NodeEntity>(
+        &self,
+        vars: (&str, &str, &str),
+    ) -> CypherQuery {
+        let (s, r, e) = vars;
+        let q = CypherQuery::new(format!(
+            "MATCH ({}:{})-[{}:{}]->({}:{})
+             RETURN {}, {}, {}",
+            s,
+            format_labels(S::labels()),
+            r,
+            Self::to_query_obj(Some(r), StampMode::Read),
+            e,
+            format_labels(E::labels()),
+            s,
+            r,
+            e
+        ));
+        q.add_values(self, Some(r), StampMode::Read)
+    }
+    /// Reads relationship(s) going out of a specific node.
     fn read_from<T: NodeId>(&self, from: &T) -> Query {
-        let mut q = Query::new(format!(
-            "MATCH (n:{})-[r:{}]-()
+        self.read_from_query::<T>(from).into()
+    }
+    /// Like [RelationId::read_from], as a [CypherQuery] whose text and parameters can be read.
+    fn read_from_query<T: NodeId>(&self, from: &T) -> CypherQuery {
+        self.read_from_directed_query(from, Direction::Outgoing)
+    }
+    /// Reads relationship(s) connected to a specific node in the given [Direction].
+    fn read_from_directed<T: NodeId>(&self, from: &T, direction: Direction) -> Query {
+        self.read_from_directed_query::<T>(from, direction).into()
+    }
+    /// Like [RelationId::read_from_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn read_from_directed_query<T: NodeId>(&self, from: &T, direction: Direction) -> CypherQuery {
+        let mut q = CypherQuery::new(format!(
+            "MATCH (n:{}){}()
              RETURN r",
             T::to_query_obj(Some("n"), StampMode::Read),
-            Self::to_query_obj(None, StampMode::Read)
+            direction.format_pattern(&format!(
+                "[r:{}]",
+                Self::to_query_obj(None, StampMode::Read)
+            )),
+        ));
+        q = q.add_values(from, Some("n"), StampMode::Read);
+        q.add_values(self, None, StampMode::Read)
+    }
+    /// Counts the relationships of this type going out of a specific node. The count is returned as "count".
+    fn count_from<T: NodeId>(from: &T) -> Query {
+        Self::count_from_query::<T>(from).into()
+    }
+    /// Like [RelationId::count_from], as a [CypherQuery] whose text and parameters can be read.
+    fn count_from_query<T: NodeId>(from: &T) -> CypherQuery {
+        Self::count_from_directed_query(from, Direction::Outgoing)
+    }
+    /// Counts the relationships of this type connected to a specific node in the given [Direction].
+    fn count_from_directed<T: NodeId>(from: &T, direction: Direction) -> Query {
+        Self::count_from_directed_query::<T>(from, direction).into()
+    }
+    /// Like [RelationId::count_from_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn count_from_directed_query<T: NodeId>(from: &T, direction: Direction) -> CypherQuery {
+        let q = CypherQuery::new(format!(
+            "MATCH (n:{}){}() RETURN count(r) AS count",
+            T::to_query_obj(Some("n"), StampMode::Read),
+            direction.format_pattern(&format!("[r:{}]", format_labels(Self::labels()))),
         ));
-        q = from.add_values_to_params(q, Some("n"), StampMode::Read);
-        self.add_values_to_params(q, None, StampMode::Read)
+        q.add_values(from, Some("n"), StampMode::Read)
+    }
+    /// Reads one [Page] of the relationships of this type going out of a specific node.
+    ///
+    /// Uses "n" as the variable for the node and "r" for each relationship.
+    /// Returns a single row, which decodes into a [Paged] of [RelationId::T].
+    /// Fails if the page is ordered by a field of another type.
+    fn list_from<T: NodeId>(from: &T, page: &Page) -> Result<Query, Error> {
+        Self::list_from_query::<T>(from, page).map(Into::into)
+    }
+    /// Like [RelationId::list_from], as a [CypherQuery] whose text and parameters can be read.
+    fn list_from_query<T: NodeId>(from: &T, page: &Page) -> Result<CypherQuery, Error> {
+        let pattern = format!(
+            "(n:{})-[r:{}]->()",
+            T::to_query_obj(Some("n"), StampMode::Read),
+            format_labels(Self::labels()),
+        );
+        let (q, params) = page.to_query(&pattern, Self::typename(), "r", Self::field_names())?;
+        Ok(params
+            .into_query(q)
+            .add_values(from, Some("n"), StampMode::Read))
     }
-    /// Reads relationship(s) connected between two specific nodes.
+    /// Reads relationship(s) going from `start` to `end`.
     fn read_between<S: NodeId, E: NodeId>(&self, start: &S, end: &E) -> Query {
-        let mut q = Query::new(format!(
-            "MATCH (s:{})-[r:{}]-(e:{})
+        self.read_between_query::<S, E>(start, end).into()
+    }
+    /// Like [RelationId::read_between], as a [CypherQuery] whose text and parameters can be read.
+    fn read_between_query<S: NodeId, E: NodeId>(&self, start: &S, end: &E) -> CypherQuery {
+        self.read_between_directed_query(start, end, Direction::Outgoing)
+    }
+    /// Reads relationship(s) connected between two specific nodes in the given [Direction].
+    fn read_between_directed<S: NodeId, E: NodeId>(
+        &self,
+        start: &S,
+        end: &E,
+        direction: Direction,
+    ) -> Query {
+        self.read_between_directed_query::<S, E>(start, end, direction)
+            .into()
+    }
+    /// Like [RelationId::read_between_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn read_between_directed_query<S: NodeId, E: NodeId>(
+        &self,
+        start: &S,
+        end: &E,
+        direction: Direction,
+    ) -> CypherQuery {
+        let mut q = CypherQuery::new(format!(
+            "MATCH (s:{}){}(e:{})
              RETURN r",
             S::to_query_obj(Some("s"), StampMode::Read),
-            Self::to_query_obj(None, StampMode::Read),
+            direction.format_pattern(&format!(
+                "[r:{}]",
+                Self::to_query_obj(None, StampMode::Read)
+            )),
             E::to_query_obj(Some("e"), StampMode::Read),
         ));
-        q = start.add_values_to_params(q, Some("s"), StampMode::Read);
-        q = end.add_values_to_params(q, Some("e"), StampMode::Read);
-        self.add_values_to_params(q, None, StampMode::Read)
+        q = q.add_values(start, Some("s"), StampMode::Read);
+        q = q.add_values(end, Some("e"), StampMode::Read);
+        q.add_values(self, None, StampMode::Read)
     }
     /// Use only for relations that have one or more ID fields, otherwise use the other `delete_` methods.
     ///
     /// This will delete all relations of the same type if [FieldSet::field_names()] is empty.
     fn delete(&self) -> Query {
+        self.delete_query().into()
+    }
+    /// Like [RelationId::delete], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_query(&self) -> CypherQuery {
         assert!(!Self::field_names().is_empty());
-        let q = Query::new(format!(
-            "MATCH [r:{}] DELETE r",
+        let q = CypherQuery::new(format!(
+            "MATCH ()-[r:{}]->() DELETE r",
             Self::to_query_obj(None, StampMode::Read)
         ));
-        self.add_values_to_params(q, None, StampMode::Read)
+        q.add_values(self, None, StampMode::Read)
     }
-    /// Deletes relationship(s) connected to a specific node.
+    /// Deletes relationship(s) going out of a specific node.
     fn delete_from<T: NodeId>(&self, from: &T) -> Query {
-        let mut q = Query::new(format!(
-            "MATCH (n:{})-[r:{}]-()
+        self.delete_from_query::<T>(from).into()
+    }
+    /// Like [RelationId::delete_from], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_from_query<T: NodeId>(&self, from: &T) -> CypherQuery {
+        self.delete_from_directed_query(from, Direction::Outgoing)
+    }
+    /// Deletes relationship(s) connected to a specific node in the given [Direction].
+    fn delete_from_directed<T: NodeId>(&self, from: &T, direction: Direction) -> Query {
+        self.delete_from_directed_query::<T>(from, direction).into()
+    }
+    /// Like [RelationId::delete_from_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_from_directed_query<T: NodeId>(&self, from: &T, direction: Direction) -> CypherQuery {
+        let mut q = CypherQuery::new(format!(
+            "MATCH (n:{}){}()
              DELETE r",
             T::to_query_obj(Some("n"), StampMode::Read),
-            Self::to_query_obj(None, StampMode::Read)
+            direction.format_pattern(&format!(
+                "[r:{}]",
+                Self::to_query_obj(None, StampMode::Read)
+            )),
         ));
-        q = from.add_values_to_params(q, Some("n"), StampMode::Read);
-        self.add_values_to_params(q, None, StampMode::Read)
+        q = q.add_values(from, Some("n"), StampMode::Read);
+        q.add_values(self, None, StampMode::Read)
     }
-    /// Deletes relationship(s) connected between two specific nodes.
+    /// Deletes relationship(s) going from `start` to `end`.
     fn delete_between<S: NodeId, E: NodeId>(&self, start: &S, end: &E) -> Query {
-        let mut q = Query::new(format!(
-            "MATCH (s:{})-[r:{}]-(e:{})
+        self.delete_between_query::<S, E>(start, end).into()
+    }
+    /// Like [RelationId::delete_between], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_between_query<S: NodeId, E: NodeId>(&self, start: &S, end: &E) -> CypherQuery {
+        self.delete_between_directed_query(start, end, Direction::Outgoing)
+    }
+    /// Deletes relationship(s) connected between two specific nodes in the given [Direction].
+    fn delete_between_directed<S: NodeId, E: NodeId>(
+        &self,
+        start: &S,
+        end: &E,
+        direction: Direction,
+    ) -> Query {
+        self.delete_between_directed_query::<S, E>(start, end, direction)
+            .into()
+    }
+    /// Like [RelationId::delete_between_directed], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_between_directed_query<S: NodeId, E: NodeId>(
+        &self,
+        start: &S,
+        end: &E,
+        direction: Direction,
+    ) -> CypherQuery {
+        let mut q = CypherQuery::new(format!(
+            "MATCH (s:{}){}(e:{})
              DELETE r",
             S::to_query_obj(Some("s"), StampMode::Read),
-            Self::to_query_obj(None, StampMode::Read),
+            direction.format_pattern(&format!(
+                "[r:{}]",
+                Self::to_query_obj(None, StampMode::Read)
+            )),
             E::to_query_obj(Some("e"), StampMode::Read),
         ));
-        q = start.add_values_to_params(q, Some("s"), StampMode::Read);
-        q = end.add_values_to_params(q, Some("e"), StampMode::Read);
-        self.add_values_to_params(q, None, StampMode::Read)
+        q = q.add_values(start, Some("s"), StampMode::Read);
+        q = q.add_values(end, Some("e"), StampMode::Read);
+        q.add_values(self, None, StampMode::Read)
+    }
+    /// Reads many relationships going from `start` to `end`, using "s", "r", and "e" as the variables.
+    ///
+    /// Each item is a `(start, relationship, end)` tuple. The rows are returned in the same order as `items`.
+    fn read_many<S: NodeId, E: NodeId>(items: &[(S, Self, E)]) -> Query {
+        Self::read_many_query::<S, E>(items).into()
+    }
+    /// Like [RelationId::read_many], as a [CypherQuery] whose text and parameters can be read.
+    fn read_many_query<S: NodeId, E: NodeId>(items: &[(S, Self, E)]) -> CypherQuery {
+        let body = format!(
+            "MATCH (s:{})-[r:{}]->(e:{}) RETURN r ORDER BY i",
+            format_row_obj::<S>("row.s"),
+            format_row_obj::<Self>("row.r"),
+            format_row_obj::<E>("row.e"),
+        );
+        CypherQuery::new(format_unwind_indexed(&body)).param(
+            "rows",
+            rows_param(
+                items
+                    .iter()
+                    .map(|(s, r, e)| bound_row(s, r.to_bolt_map(StampMode::Read), e)),
+            ),
+        )
+    }
+    /// Deletes many relationships going from `start` to `end`, using "s", "r", and "e" as the variables.
+    ///
+    /// Each item is a `(start, relationship, end)` tuple.
+    ///
+    /// `chunk_size` is the same as for [NodeEntity::create_many].
+    fn delete_many<S: NodeId, E: NodeId>(
+        items: &[(S, Self, E)],
+        chunk_size: Option<usize>,
+    ) -> Query {
+        Self::delete_many_query::<S, E>(items, chunk_size).into()
+    }
+    /// Like [RelationId::delete_many], as a [CypherQuery] whose text and parameters can be read.
+    fn delete_many_query<S: NodeId, E: NodeId>(
+        items: &[(S, Self, E)],
+        chunk_size: Option<usize>,
+    ) -> CypherQuery {
+        let body = format!(
+            "MATCH (s:{})-[r:{}]->(e:{}) DELETE r",
+            format_row_obj::<S>("row.s"),
+            format_row_obj::<Self>("row.r"),
+            format_row_obj::<E>("row.e"),
+        );
+        CypherQuery::new(format_unwind(&body, chunk_size)).param(
+            "rows",
+            rows_param(
+                items
+                    .iter()
+                    .map(|(s, r, e)| bound_row(s, r.to_bolt_map(StampMode::Read), e)),
+            ),
+        )
     }
 }
 
+/// One row of a batch relationship query, with the start, relationship, and end values under "s", "r", and "e".
+fn bound_row<S: NodeId, E: NodeId>(start: &S, relation: BoltMap, end: &E) -> BoltMap {
+    [
+        ("s", start.to_bolt_map(StampMode::Read)),
+        ("r", relation),
+        ("e", end.to_bolt_map(StampMode::Read)),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.into(), BoltType::Map(v)))
+    .collect()
+}
+
 /// When creating a relationship, the query has to MATCH, CREATE, or MERGE the
 /// start and end nodes.
 pub enum RelationBound<'a, T: NodeEntity> {
     Create(&'a T),
     Match(&'a T::Id),
-    // TODO Cannot complete implementation due to add_params_to_query not having enough info.
-    // Merge(T),
+    /// Creates the node or updates an existing one, the same as [NodeEntity::merge].
+    Merge(&'a T),
 }
 impl<'a, T: NodeEntity> RelationBound<'a, T> {
-    /// Returns a CREATE (node:...) or MATCH (node:...) clause for this variant.
+    /// Returns a CREATE (node:...), MATCH (node:...), or MERGE (node:...) clause for this variant.
     pub fn to_query_clause(&self, prefix: &str) -> String {
         match self {
             RelationBound::Create(_) => format!(
@@ -192,29 +752,125 @@ impl<'a, T: NodeEntity> RelationBound<'a, T> {
                 prefix,
                 T::Id::to_query_obj(Some(prefix), StampMode::Read)
             ),
-            // RelationBound::Merge(_) => {
-            //     format!(
-            //         r###"
-            //         MERGE ({prefix})
-            //         ON CREATE
-            //             SET {prefix} = {}
-            //         ON MATCH
-            //             SET {prefix} += {}
-            //         "###,
-            //         T::as_query_obj(Some(prefix), StampMode::Create),
-            //         T::as_query_obj(Some(prefix), StampMode::Update),
-            //     )
-            // }
+            RelationBound::Merge(_) => merge_clause::<T>(prefix, Some(prefix)),
+        }
+    }
+    /// The parameters for [RelationBound::to_query_clause], without the prefix.
+    pub fn to_bolt_map(&self) -> BoltMap {
+        match self {
+            RelationBound::Create(t) => t.to_bolt_map(StampMode::Create),
+            RelationBound::Match(id) => id.to_bolt_map(StampMode::Read),
+            RelationBound::Merge(t) => t.to_bolt_map(StampMode::Merge),
         }
     }
-    pub fn add_params(&self, q: Query, prefix: &str) -> Query {
+    /// Adds the parameters for [RelationBound::to_query_clause] to `q`.
+    pub fn add_params(&self, q: CypherQuery, prefix: &str) -> CypherQuery {
+        q.add_map(self.to_bolt_map(), Some(prefix))
+    }
+}
+
+/// A relationship and the nodes it connects, as returned by `RETURN s, r, e`.
+///
+/// The nodes can be full entities or just their ids, e.g. `Bound<Person, Knows, Person>` or
+/// `Bound<PersonId, Knows, PersonId>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bound<S, R, E> {
+    pub start: S,
+    pub relation: R,
+    pub end: E,
+}
+impl<S, R, E> Bound<S, R, E> {
+    pub fn into_tuple(self) -> (S, R, E) {
+        (self.start, self.relation, self.end)
+    }
+}
+impl<S, R, E> From<Bound<S, R, E>> for (S, R, E) {
+    fn from(value: Bound<S, R, E>) -> Self {
+        value.into_tuple()
+    }
+}
+impl<S, R, E> TryFrom<Row> for Bound<S, R, E>
+where
+    S: TryFrom<Node>,
+    S::Error: Display,
+    R: TryFrom<Relation>,
+    R::Error: Display,
+    E: TryFrom<Node>,
+    E::Error: Display,
+{
+    type Error = Error;
+    fn try_from(value: Row) -> Result<Self, Self::Error> {
+        Self::from_row_as(&value, ("s", "r", "e"))
+    }
+}
+impl<S, R, E> Bound<S, R, E>
+where
+    S: TryFrom<Node>,
+    S::Error: Display,
+    R: TryFrom<Relation>,
+    R::Error: Display,
+    E: TryFrom<Node>,
+    E::Error: Display,
+{
+    /// Decodes a row with the `(start, relationship, end)` columns named `columns`, e.g. from [RelationId::read_bound_as].
+    pub fn from_row_as(row: &Row, columns: (&str, &str, &str)) -> Result<Self, Error> {
+        let (s, r, e) = columns;
+        let start = row
+            .get::<Node>(s)
+            .map_err(|_e| Error::MissingField(s.to_owned()))?;
+        let relation = row
+            .get::<Relation>(r)
+            .map_err(|_e| Error::MissingField(r.to_owned()))?;
+        let end = row
+            .get::<Node>(e)
+            .map_err(|_e| Error::MissingField(e.to_owned()))?;
+        Ok(Self {
+            start: S::try_from(start).map_err(|e| Error::FromMapInner(e.to_string()))?,
+            relation: R::try_from(relation).map_err(|e| Error::FromMapInner(e.to_string()))?,
+            end: E::try_from(end).map_err(|e| Error::FromMapInner(e.to_string()))?,
+        })
+    }
+}
+
+/// The direction of a relationship, relative to the first node in a pattern.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// `(n)-[r]->()`. This is the direction [RelationEntity::create] uses.
+    #[default]
+    Outgoing,
+    /// `(n)<-[r]-()`
+    Incoming,
+    /// `(n)-[r]-()`, which matches relationships in both directions.
+    Either,
+}
+impl Direction {
+    /// Adds the arrows for this direction to a relationship pattern.
+    ///
+    /// `[r:KNOWS]` would become `-[r:KNOWS]->`, `<-[r:KNOWS]-`, or `-[r:KNOWS]-`.
+    pub fn format_pattern(&self, rel: &str) -> String {
         match self {
-            RelationBound::Create(t) => t.add_values_to_params(q, Some(prefix), StampMode::Create),
-            RelationBound::Match(id) => id.add_values_to_params(q, Some(prefix), StampMode::Read),
-            // TODO cannot know how to add params due to ON CREATE vs ON MATCH.
-            // RelationBound::Merge(t) => {
-            //     t.add_values_to_params(q, Some(prefix) /*not enough info */)
-            // }
+            Direction::Outgoing => format!("-{}->", rel),
+            Direction::Incoming => format!("<-{}-", rel),
+            Direction::Either => format!("-{}-", rel),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction() {
+        assert_eq!(Direction::default(), Direction::Outgoing);
+        assert_eq!(
+            Direction::Outgoing.format_pattern("[r:KNOWS]"),
+            "-[r:KNOWS]->"
+        );
+        assert_eq!(
+            Direction::Incoming.format_pattern("[r:KNOWS]"),
+            "<-[r:KNOWS]-"
+        );
+        assert_eq!(Direction::Either.format_pattern("[r]"), "-[r]-");
+    }
+}
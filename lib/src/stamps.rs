@@ -1,6 +1,6 @@
 use chrono::{DateTime, FixedOffset, Utc};
 
-use crate::{format_query_fields, StampMode};
+use crate::{escape_identifier, format_query_fields, StampMode};
 
 /// The standard timestamps an object uses, and their field names.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -22,7 +22,7 @@ impl Stamps {
         let mut has_updated = false;
         let mut non_stamp_fields = Vec::new();
         for field in fields {
-            match field.as_ref() {
+            match *field {
                 "created_at" => has_created_at = true,
                 "updated_at" => has_updated_at = true,
                 "created" => has_created = true,
@@ -73,25 +73,36 @@ impl Stamps {
             // Hardcode datetime()
             StampMode::Create => match self {
                 Stamps::None => "".to_owned(),
-                Stamps::Created(name) => format!("{}: datetime()", name),
-                Stamps::Updated(name) => format!("{}: datetime()", name),
+                Stamps::Created(name) => format!("{}: datetime()", escape_identifier(name)),
+                Stamps::Updated(name) => format!("{}: datetime()", escape_identifier(name)),
                 Stamps::Both(created, updated) => {
-                    format!("{}: datetime(), {}: datetime()", created, updated)
+                    format!(
+                        "{}: datetime(), {}: datetime()",
+                        escape_identifier(created),
+                        escape_identifier(updated)
+                    )
                 }
             },
             // created: placeholder, updated: datetime()
             StampMode::Update => match self {
                 Stamps::None => "".to_owned(),
                 Stamps::Created(name) => format_query_fields([name], prefix),
-                Stamps::Updated(name) => format!("{}: datetime()", name),
+                Stamps::Updated(name) => format!("{}: datetime()", escape_identifier(name)),
                 Stamps::Both(created, updated) => {
                     format!(
                         "{}, {}: datetime()",
                         format_query_fields([created], prefix),
-                        updated
+                        escape_identifier(updated)
                     )
                 }
             },
+            // created: omitted, updated: datetime()
+            StampMode::Merge => match self {
+                Stamps::None | Stamps::Created(_) => "".to_owned(),
+                Stamps::Updated(name) | Stamps::Both(_, name) => {
+                    format!("{}: datetime()", escape_identifier(name))
+                }
+            },
         }
     }
 
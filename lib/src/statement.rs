@@ -0,0 +1,234 @@
+use crate::{
+    format_param, CypherQuery, FieldSet, NodeEntity, NodeId, RelationBound, RelationEntity,
+    StampMode,
+};
+use neo4rs::{BoltType, Query};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A query built from several fragments, e.g. creating and matching more than one DTO, that only
+/// becomes a [Query] at the end.
+///
+/// Each fragment gets a unique variable from [Statement::var], which is also the prefix of its
+/// parameters, e.g. `$alice_id`. Variables never contain `_`, and other parameters never start with
+/// a variable and `_`, so parameter names can't collide the way prefixes chosen by hand can
+/// (prefix `a` with field `b_c`, and prefix `a_b` with field `c`).
+///
+/// The `MATCH` clauses for nodes come first, since Cypher doesn't allow reading after writing without a `WITH`.
+///
+/// ```ignore
+/// let mut s = Statement::new();
+/// let acme = s.match_("acme", &acme_id);
+/// let alice = s.create("alice", &alice);
+/// let r = s.relate("r", &WorksAt {}, &alice, &acme);
+/// s.push(format!("RETURN {}, {}", alice, r));
+/// graph.run(s.into_query()).await?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Statement {
+    /// `MATCH` clauses, which have to come before any writes.
+    matches: Vec<String>,
+    /// The other clauses, in order.
+    clauses: Vec<String>,
+    vars: BTreeSet<String>,
+    params: BTreeMap<String, BoltType>,
+}
+impl Statement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates a variable named like `name`: only its letters and digits are kept, and a number is added if it's taken.
+    pub fn var(&mut self, name: &str) -> String {
+        let mut base: String = name.chars().filter(|c| c.is_alphanumeric()).collect();
+        if !base.starts_with(|c: char| c.is_alphabetic()) {
+            base.insert(0, 'v');
+        }
+        let mut var = base.clone();
+        let mut n = 1;
+        while self.vars.contains(&var)
+            || self
+                .params
+                .keys()
+                .any(|p| prefix_of(p) == Some(var.as_str()))
+        {
+            n += 1;
+            var = format!("{}{}", base, n);
+        }
+        self.vars.insert(var.clone());
+        var
+    }
+
+    /// Adds a parameter named `name`, or `name2`, `name3`, etc. if it's taken. Returns the name used.
+    pub fn param(&mut self, name: &str, value: impl Into<BoltType>) -> String {
+        let base = match prefix_of(name) {
+            Some(prefix) if self.vars.contains(prefix) => name.replace('_', ""),
+            _ => name.to_owned(),
+        };
+        let mut param = base.clone();
+        let mut n = 1;
+        while self.params.contains_key(&param) {
+            n += 1;
+            param = format!("{}{}", base, n);
+        }
+        self.params.insert(param.clone(), value.into());
+        param
+    }
+
+    /// Adds a clause, e.g. `RETURN n`. Clauses are kept in order, after the `MATCH` clauses for nodes.
+    pub fn push(&mut self, clause: impl Into<String>) -> &mut Self {
+        self.clauses.push(clause.into());
+        self
+    }
+
+    /// Adds the fields of `dto` as parameters prefixed with `var`, which has to come from [Statement::var].
+    pub fn fields<T: FieldSet>(&mut self, var: &str, dto: &T, mode: StampMode) {
+        debug_assert!(self.vars.contains(var), "unallocated variable: {}", var);
+        for (key, value) in dto.to_bolt_map(mode).value {
+            self.params
+                .insert(format_param(&key.value, Some(var)), value);
+        }
+    }
+
+    /// Adds a `CREATE` clause for a node. Returns its variable.
+    pub fn create<T: NodeEntity>(&mut self, name: &str, node: &T) -> String {
+        self.bound(name, RelationBound::Create(node))
+    }
+
+    /// Adds a `MATCH` clause for a node by its id. Returns its variable.
+    pub fn match_<T: NodeId>(&mut self, name: &str, id: &T) -> String {
+        self.bound::<T::T>(name, RelationBound::Match(id))
+    }
+
+    /// Adds a `MERGE` clause for a node, the same as [NodeEntity::merge]. Returns its variable.
+    pub fn merge<T: NodeEntity>(&mut self, name: &str, node: &T) -> String {
+        self.bound(name, RelationBound::Merge(node))
+    }
+
+    /// Adds the clause for a [RelationBound]. Returns its variable.
+    pub fn bound<T: NodeEntity>(&mut self, name: &str, bound: RelationBound<T>) -> String {
+        let is_match = matches!(bound, RelationBound::Match(_));
+        let (var, clause) = self.bound_clause(name, bound);
+        if is_match {
+            self.matches.push(clause);
+        } else {
+            self.clauses.push(clause);
+        }
+        var
+    }
+
+    /// Adds a `CREATE` clause for a relationship between the nodes with the variables `start` and `end`. Returns its variable.
+    pub fn relate<R: RelationEntity>(
+        &mut self,
+        name: &str,
+        rel: &R,
+        start: &str,
+        end: &str,
+    ) -> String {
+        let (var, clause) = self.relate_clause(name, rel, start, end);
+        self.clauses.push(clause);
+        var
+    }
+
+    /// Like [Statement::bound], but returns the clause instead of adding it.
+    fn bound_clause<T: NodeEntity>(
+        &mut self,
+        name: &str,
+        bound: RelationBound<T>,
+    ) -> (String, String) {
+        let var = self.var(name);
+        let clause = bound.to_query_clause(&var);
+        match bound {
+            RelationBound::Create(t) => self.fields(&var, t, StampMode::Create),
+            RelationBound::Match(id) => self.fields(&var, id, StampMode::Read),
+            RelationBound::Merge(t) => self.fields(&var, t, StampMode::Merge),
+        }
+        (var, clause)
+    }
+
+    /// Like [Statement::relate], but returns the clause instead of adding it.
+    pub(crate) fn relate_clause<R: RelationEntity>(
+        &mut self,
+        name: &str,
+        rel: &R,
+        start: &str,
+        end: &str,
+    ) -> (String, String) {
+        let var = self.var(name);
+        let clause = format!(
+            "CREATE ({})-[{}:{}]->({})",
+            start,
+            var,
+            R::to_query_obj(Some(&var), StampMode::Create),
+            end
+        );
+        self.fields(&var, rel, StampMode::Create);
+        (var, clause)
+    }
+
+    /// The clauses, one per line.
+    pub fn text(&self) -> String {
+        self.matches
+            .iter()
+            .chain(&self.clauses)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    pub fn params(&self) -> &BTreeMap<String, BoltType> {
+        &self.params
+    }
+    pub fn into_query(self) -> Query {
+        self.into_cypher_query().into()
+    }
+    /// Like [Statement::into_query], as a [CypherQuery] whose text and parameters can be read.
+    pub fn into_cypher_query(self) -> CypherQuery {
+        CypherQuery {
+            text: self.text(),
+            params: self.params,
+        }
+    }
+}
+impl From<Statement> for Query {
+    fn from(statement: Statement) -> Self {
+        statement.into_query()
+    }
+}
+impl From<Statement> for CypherQuery {
+    fn from(statement: Statement) -> Self {
+        statement.into_cypher_query()
+    }
+}
+
+/// The part of a parameter name before the first `_`, which is the variable of the fragment it's for.
+fn prefix_of(param: &str) -> Option<&str> {
+    param.split_once('_').map(|(prefix, _)| prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_names() {
+        let mut s = Statement::new();
+        assert_eq!(s.var("a"), "a");
+        assert_eq!(s.var("a_b"), "ab");
+        assert_eq!(s.var("a"), "a2");
+        assert_eq!(s.var("1st"), "v1st");
+
+        // Could collide with the fields of "a".
+        assert_eq!(s.param("a_b", 1), "ab");
+        assert_eq!(s.param("ab", 2), "ab2");
+        assert_eq!(s.param("max_age", 3), "max_age");
+        // Could collide with the fields of "max".
+        assert_eq!(s.var("max"), "max2");
+
+        s.push("RETURN $ab, $ab2, $max_age");
+        assert_eq!(s.text(), "RETURN $ab, $ab2, $max_age");
+        assert_eq!(
+            s.params().keys().collect::<Vec<_>>(),
+            ["ab", "ab2", "max_age"]
+        );
+        assert!(s.into_query().has_param_key("ab2"));
+    }
+}
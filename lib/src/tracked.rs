@@ -0,0 +1,120 @@
+use crate::{patch::format_patch_fields, CypherQuery, FieldSet, NodeEntity, NodeId, StampMode};
+use neo4rs::{BoltMap, Query};
+use std::ops::Deref;
+
+/// A node that remembers its original values, so that only the changed fields are written.
+///
+/// ```ignore
+/// let alice = Tracked::new(alice);
+/// let alice = alice.map(|p| p.into_builder().age(Some(42)).build());
+/// if let Some(q) = alice.build_changes() {
+///     graph.run(q).await?;
+/// }
+/// ```
+///
+/// Timestamps aren't tracked. The changes are written to the node with the original id, so changing
+/// the id fields renames the node, the same as [NodeEntity::update_with_id].
+pub struct Tracked<T: NodeEntity> {
+    id: T::Id,
+    original: BoltMap,
+    value: T,
+}
+impl<T: NodeEntity> Tracked<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            id: value.identifier(),
+            original: tracked_values(&value),
+            value,
+        }
+    }
+
+    /// Changes the value, e.g. with `|p| p.into_builder().name("Bob").build()`.
+    pub fn map(mut self, f: impl FnOnce(T) -> T) -> Self {
+        self.value = f(self.value);
+        self
+    }
+
+    /// Replaces the value.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+    }
+
+    /// The id the node had when it started being tracked.
+    pub fn original_id(&self) -> &T::Id {
+        &self.id
+    }
+
+    /// The fields that changed, with their current values.
+    pub fn changes(&self) -> BoltMap {
+        tracked_values(&self.value)
+            .value
+            .into_iter()
+            .filter(|(k, v)| self.original.value.get(k) != Some(v))
+            .collect()
+    }
+
+    pub fn is_changed(&self) -> bool {
+        !self.changes().value.is_empty()
+    }
+
+    /// Updates only the changed fields and the updated timestamp, the same as [NodeId::patch].
+    ///
+    /// If any id fields changed, the node is matched by its original id with parameters prefixed with "old",
+    /// and the changes are prefixed with "n", the same as [NodeEntity::update_with_id].
+    ///
+    /// Returns `None` if nothing changed, so no-op saves don't touch the node.
+    pub fn build_changes(&self) -> Option<Query> {
+        self.build_changes_query().map(Into::into)
+    }
+    /// Like [Tracked::build_changes], as a [CypherQuery] whose text and parameters can be read.
+    pub fn build_changes_query(&self) -> Option<CypherQuery> {
+        let changes = self.changes();
+        if changes.value.is_empty() {
+            return None;
+        }
+        let ids = T::Id::field_names();
+        if !changes
+            .value
+            .keys()
+            .any(|k| ids.contains(&k.value.as_str()))
+        {
+            return Some(self.id.patch_query(changes));
+        }
+        let q = CypherQuery::new(format!(
+            "MATCH (n:{}) SET n += {{ {} }}",
+            T::Id::to_query_obj(Some("old"), StampMode::Read),
+            format_patch_fields::<T>(&changes, Some("n")),
+        ));
+        Some(
+            q.add_values(&self.id, Some("old"), StampMode::Read)
+                .add_map(changes, Some("n")),
+        )
+    }
+
+    /// Treats the current values as the original ones, e.g. after running [Tracked::build_changes].
+    pub fn reset(&mut self) {
+        self.id = self.value.identifier();
+        self.original = tracked_values(&self.value);
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+impl<T: NodeEntity> Deref for Tracked<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// The values of the fields that are tracked, which excludes the timestamps.
+fn tracked_values<T: NodeEntity>(value: &T) -> BoltMap {
+    let (_, fields) = T::timestamps();
+    value
+        .to_bolt_map(StampMode::Read)
+        .value
+        .into_iter()
+        .filter(|(k, _)| fields.contains(&k.value.as_str()))
+        .collect()
+}
@@ -64,3 +64,261 @@ async fn basic_crud() {
     let row = stream.next().await.unwrap();
     assert!(row.is_none());
 }
+
+#[tokio::test]
+async fn relation_direction() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let alice = Person::new(&uuid(), "Alice", None);
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    let worked_at = WorkedAt { until: Utc::now() };
+    graph
+        .run(worked_at.create(RelationBound::Create(&alice), RelationBound::Create(&acme)))
+        .await
+        .unwrap();
+
+    let alice_id = alice.identifier();
+    let acme_id = acme.identifier();
+    let id = worked_at.identifier();
+
+    // (alice)-[:WORKED_AT]->(acme), so it is incoming from acme's point of view.
+    let mut stream = graph
+        .execute(id.read_between(&acme_id, &alice_id))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+
+    let mut stream = graph
+        .execute(id.read_between_directed(&acme_id, &alice_id, Direction::Incoming))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_some());
+
+    // Either direction still matches the relationship only once from a given node.
+    let mut stream = graph
+        .execute(id.read_from_directed(&acme_id, Direction::Either))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_some());
+    assert!(stream.next().await.unwrap().is_none());
+
+    // Deleting in the wrong direction leaves the relationship alone.
+    graph
+        .run(id.delete_between(&acme_id, &alice_id))
+        .await
+        .unwrap();
+    let mut stream = graph
+        .execute(id.read_between(&alice_id, &acme_id))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_some());
+
+    graph
+        .run(id.delete_between(&alice_id, &acme_id))
+        .await
+        .unwrap();
+    let mut stream = graph
+        .execute(id.read_between(&alice_id, &acme_id))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn merge() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let alice = Person::new(&uuid(), "Alice", None);
+    let read_alice = || async {
+        let mut stream = graph.execute(alice.identifier().read()).await.unwrap();
+        let row = stream.next().await.unwrap().unwrap();
+        assert!(stream.next().await.unwrap().is_none());
+        Person::try_from(row.get::<Node>("n").unwrap()).unwrap()
+    };
+
+    // Creates the node when it doesn't exist.
+    graph.run(alice.merge()).await.unwrap();
+    let created = read_alice().await;
+    assert_eq!(created.name(), "Alice");
+    assert_eq!(created.created_at(), created.updated_at());
+
+    // Updates it when it does, keeping the created timestamp.
+    let allison = alice
+        .clone()
+        .into_builder()
+        .name("Allison".to_owned())
+        .build()
+        .unwrap();
+    graph.run(allison.merge()).await.unwrap();
+    let merged = read_alice().await;
+    assert_eq!(merged.name(), "Allison");
+    assert_eq!(merged.created_at(), created.created_at());
+    assert!(merged.updated_at() > created.updated_at());
+
+    // Relationship endpoints can be merged too.
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    for _ in 0..2 {
+        graph
+            .run(WorksAt {}.create(RelationBound::Merge(&allison), RelationBound::Merge(&acme)))
+            .await
+            .unwrap();
+    }
+    let mut stream = graph
+        .execute(
+            Query::new("MATCH (p:Person { id: $id }) RETURN count(p) AS people".to_owned())
+                .param("id", allison.id()),
+        )
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<i64>("people").unwrap(), 1);
+}
+
+#[tokio::test]
+async fn relation_merge() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let alice = Person::new(&uuid(), "Alice", None);
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    graph.run(alice.create()).await.unwrap();
+    graph.run(acme.create()).await.unwrap();
+
+    for _ in 0..2 {
+        graph
+            .run(WorksAt {}.merge(
+                RelationBound::<Person>::Match(&alice.identifier()),
+                RelationBound::<Company>::Match(&acme.identifier()),
+            ))
+            .await
+            .unwrap();
+    }
+    let mut stream = graph
+        .execute(
+            WorksAt {}
+                .identifier()
+                .read_between(&alice.identifier(), &acme.identifier()),
+        )
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_some());
+    assert!(stream.next().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn batch() {
+    let neo4j = Neo4jContainer::new().await;
+    let graph = neo4j.graph();
+
+    let people: Vec<Person> = (0..10)
+        .map(|i| Person::new(&uuid(), &format!("Person {i}"), Some(i)))
+        .collect();
+    graph.run(Person::create_many(&people, None)).await.unwrap();
+
+    // Read in reverse, with one missing id.
+    let mut ids: Vec<PersonId> = people.iter().rev().map(|p| p.identifier()).collect();
+    ids.insert(3, PersonId { id: uuid() });
+    let mut stream = graph.execute(PersonId::read_many(&ids)).await.unwrap();
+    let mut names = Vec::new();
+    while let Some(row) = stream.next().await.unwrap() {
+        let person = Person::try_from(row.get::<Node>("n").unwrap()).unwrap();
+        assert!(person.created_at().is_some());
+        names.push(person.name().to_owned());
+    }
+    let expected: Vec<String> = people.iter().rev().map(|p| p.name().to_owned()).collect();
+    assert_eq!(names, expected);
+
+    // Merge updates the existing people and creates a new one.
+    let mut renamed: Vec<Person> = people
+        .iter()
+        .map(|p| {
+            p.clone()
+                .into_builder()
+                .name(format!("{} (renamed)", p.name()))
+                .build()
+                .unwrap()
+        })
+        .collect();
+    renamed.push(Person::new(&uuid(), "New", None));
+    graph
+        .run(Person::merge_many(&renamed, Some(4)))
+        .await
+        .unwrap();
+    let ids: Vec<PersonId> = renamed.iter().map(|p| p.identifier()).collect();
+    let mut stream = graph.execute(PersonId::read_many(&ids)).await.unwrap();
+    let mut count = 0;
+    while let Some(row) = stream.next().await.unwrap() {
+        let person = Person::try_from(row.get::<Node>("n").unwrap()).unwrap();
+        assert_eq!(person.name(), renamed[count].name());
+        count += 1;
+    }
+    assert_eq!(count, renamed.len());
+
+    // Relationships
+    let acme = Company {
+        name: uuid(),
+        state: "CA".to_owned(),
+        created: Utc::now(),
+        updated: Utc::now(),
+    };
+    graph.run(acme.create()).await.unwrap();
+    let works_at: Vec<(PersonId, WorksAt, CompanyId)> = ids
+        .iter()
+        .map(|id| (id.clone(), WorksAt {}, acme.identifier()))
+        .collect();
+    graph
+        .run(WorksAt::create_many(&works_at, None))
+        .await
+        .unwrap();
+    graph
+        .run(WorksAt::merge_many(&works_at, None))
+        .await
+        .unwrap();
+    let works_at_ids: Vec<(PersonId, WorksAtId, CompanyId)> = works_at
+        .iter()
+        .map(|(s, r, e)| (s.clone(), r.identifier(), e.clone()))
+        .collect();
+    let mut stream = graph
+        .execute(WorksAtId::read_many(&works_at_ids))
+        .await
+        .unwrap();
+    let mut count = 0;
+    while stream.next().await.unwrap().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, works_at.len());
+    graph
+        .run(WorksAtId::delete_many(&works_at_ids, None))
+        .await
+        .unwrap();
+    let mut stream = graph
+        .execute(WorksAtId::read_many(&works_at_ids))
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+
+    // Nodes
+    graph
+        .run(PersonId::delete_many(&ids, Some(4)))
+        .await
+        .unwrap();
+    let mut stream = graph.execute(PersonId::read_many(&ids)).await.unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+}
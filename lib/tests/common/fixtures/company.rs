@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use cypher_dto::{format_param, Error, FieldSet, Neo4jMap, NodeEntity, NodeId, StampMode};
-use neo4rs::{Node, Query, Row};
+use neo4rs::{BoltMap, Node, Query, Row};
 
 /// Has a multi-valued ID and required timestamps.
 #[derive(Clone, Debug, PartialEq)]
@@ -35,7 +35,7 @@ impl FieldSet for Company {
         q = q.param(&format_param("name", prefix), self.name.clone());
         q = q.param(&format_param("state", prefix), self.state.clone());
         match mode {
-            StampMode::Create => q,
+            StampMode::Create | StampMode::Merge => q,
             StampMode::Read => q
                 .param(
                     &format_param("created", prefix),
@@ -51,6 +51,20 @@ impl FieldSet for Company {
             ),
         }
     }
+    fn to_bolt_map(&self, mode: StampMode) -> BoltMap {
+        let mut map = BoltMap::new();
+        map.put("name".into(), self.name.clone().into());
+        map.put("state".into(), self.state.clone().into());
+        match mode {
+            StampMode::Create | StampMode::Merge => (),
+            StampMode::Read => {
+                map.put("created".into(), self.created.fixed_offset().into());
+                map.put("updated".into(), self.updated.fixed_offset().into());
+            }
+            StampMode::Update => map.put("created".into(), self.created.fixed_offset().into()),
+        }
+        map
+    }
 }
 impl TryFrom<Row> for Company {
     type Error = Error;
@@ -149,6 +163,12 @@ impl FieldSet for CompanyId {
             .param(&format_param("name", prefix), self.name.clone())
             .param(&format_param("state", prefix), self.state.clone())
     }
+    fn to_bolt_map(&self, _: StampMode) -> BoltMap {
+        let mut map = BoltMap::new();
+        map.put("name".into(), self.name.clone().into());
+        map.put("state".into(), self.state.clone().into());
+        map
+    }
 }
 impl TryFrom<Row> for CompanyId {
     type Error = Error;
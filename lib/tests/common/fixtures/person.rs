@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use cypher_dto::{format_param, Error, FieldSet, Neo4jMap, NodeEntity, NodeId, StampMode};
-use neo4rs::{Node, Query, Row};
+use neo4rs::{BoltMap, Node, Query, Row};
 
 /// Single ID field and optional timestamps. Has example of `new()` and `into_builder()` methods.
 #[derive(Clone, Debug, PartialEq)]
@@ -63,7 +63,7 @@ impl FieldSet for Person {
         q = q.param(&format_param("name", prefix), self.name.clone());
         q = q.param(&format_param("age", prefix), self.age.map(u16::from));
         match mode {
-            StampMode::Create => q,
+            StampMode::Create | StampMode::Merge => q,
             StampMode::Read => q
                 .param(
                     &format_param("created_at", prefix),
@@ -79,6 +79,30 @@ impl FieldSet for Person {
             ),
         }
     }
+    fn to_bolt_map(&self, mode: StampMode) -> BoltMap {
+        let mut map = BoltMap::new();
+        map.put("id".into(), self.id.clone().into());
+        map.put("name".into(), self.name.clone().into());
+        map.put("age".into(), self.age.map(u16::from).into());
+        match mode {
+            StampMode::Create | StampMode::Merge => (),
+            StampMode::Read => {
+                map.put(
+                    "created_at".into(),
+                    self.created_at.map(|v| v.fixed_offset()).into(),
+                );
+                map.put(
+                    "updated_at".into(),
+                    self.updated_at.map(|v| v.fixed_offset()).into(),
+                );
+            }
+            StampMode::Update => map.put(
+                "created_at".into(),
+                self.created_at.map(|v| v.fixed_offset()).into(),
+            ),
+        }
+        map
+    }
 }
 
 impl TryFrom<Row> for Person {
@@ -182,6 +206,11 @@ impl FieldSet for PersonId {
     fn add_values_to_params(&self, query: Query, prefix: Option<&str>, _: StampMode) -> Query {
         query.param(&format_param("id", prefix), self.id.clone())
     }
+    fn to_bolt_map(&self, _: StampMode) -> BoltMap {
+        let mut map = BoltMap::new();
+        map.put("id".into(), self.id.clone().into());
+        map
+    }
 }
 impl TryFrom<Row> for PersonId {
     type Error = Error;
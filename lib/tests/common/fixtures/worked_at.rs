@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use cypher_dto::{format_param, Error, FieldSet, Neo4jMap, RelationEntity, RelationId, StampMode};
-use neo4rs::{Query, Relation, Row, UnboundedRelation};
+use neo4rs::{BoltMap, Query, Relation, Row, UnboundedRelation};
 
 /// A relation with an ID field.
 ///
@@ -31,6 +31,11 @@ impl FieldSet for WorkedAt {
     fn add_values_to_params(&self, q: Query, prefix: Option<&str>, _mode: StampMode) -> Query {
         q.param(&format_param("until", prefix), self.until.fixed_offset())
     }
+    fn to_bolt_map(&self, _mode: StampMode) -> BoltMap {
+        let mut map = BoltMap::new();
+        map.put("until".into(), self.until.fixed_offset().into());
+        map
+    }
 }
 impl TryFrom<Row> for WorkedAt {
     type Error = Error;
@@ -116,6 +121,11 @@ impl FieldSet for WorkedAtId {
     fn add_values_to_params(&self, q: Query, prefix: Option<&str>, _mode: StampMode) -> Query {
         q.param(&format_param("until", prefix), self.until.fixed_offset())
     }
+    fn to_bolt_map(&self, _mode: StampMode) -> BoltMap {
+        let mut map = BoltMap::new();
+        map.put("until".into(), self.until.fixed_offset().into());
+        map
+    }
 }
 impl TryFrom<Row> for WorkedAtId {
     type Error = Error;
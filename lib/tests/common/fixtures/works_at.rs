@@ -1,5 +1,5 @@
 use cypher_dto::{Error, FieldSet, RelationEntity, RelationId, StampMode};
-use neo4rs::{Query, Relation, Row, UnboundedRelation};
+use neo4rs::{BoltMap, Query, Relation, Row, UnboundedRelation};
 
 /// A fieldless relation.
 #[derive(Clone, Debug, PartialEq)]
@@ -26,6 +26,9 @@ impl FieldSet for WorksAt {
     fn add_values_to_params(&self, query: Query, _: Option<&str>, _: StampMode) -> Query {
         query
     }
+    fn to_bolt_map(&self, _: StampMode) -> BoltMap {
+        BoltMap::new()
+    }
 }
 impl TryFrom<Row> for WorksAt {
     type Error = Error;
@@ -97,6 +100,9 @@ impl FieldSet for WorksAtId {
     fn add_values_to_params(&self, query: Query, _prefix: Option<&str>, _mode: StampMode) -> Query {
         query
     }
+    fn to_bolt_map(&self, _mode: StampMode) -> BoltMap {
+        BoltMap::new()
+    }
 }
 impl TryFrom<Row> for WorksAtId {
     type Error = Error;
@@ -69,6 +69,30 @@ async fn create_all_at_once() {
 
     let worked_at2_db: WorkedAt = row.get::<Relation>("w2").unwrap().try_into().unwrap();
     assert_eq!(worked_at2, worked_at2_db);
+
+    // Read a relation back together with the nodes it connects.
+    let mut stream = graph
+        .execute(worked_at.identifier().read_bound::<Person, Company>())
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    assert!(stream.next().await.unwrap().is_none());
+    let bound: Bound<Person, WorkedAt, Company> = row.try_into().unwrap();
+    assert_eq!(bound.start.id(), bob.id());
+    assert_eq!(bound.relation, worked_at);
+    assert_eq!(bound.end.identifier(), acme.identifier());
+
+    // Or just their ids.
+    let mut stream = graph
+        .execute(worked_at2.identifier().read_bound::<Person, Company>())
+        .await
+        .unwrap();
+    let row = stream.next().await.unwrap().unwrap();
+    let (bob_id, w2, acme_id): (PersonId, WorkedAt, CompanyId) =
+        Bound::try_from(row).unwrap().into();
+    assert_eq!(bob_id, bob.identifier());
+    assert_eq!(w2, worked_at2);
+    assert_eq!(acme_id, acme.identifier());
 }
 
 // #[tokio::test]
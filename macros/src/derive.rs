@@ -1,9 +1,13 @@
 mod entity;
 mod node;
+mod projection;
 mod relation;
+mod row;
 
 pub use node::Node;
+pub use projection::Projection;
 pub use relation::Relation;
+pub use row::CypherRow;
 
 use quote::{__private::TokenStream, quote};
 use syn::{
@@ -29,15 +33,28 @@ pub enum EntityType {
 
 /// Get the value as in `#[name("Foo")]`
 pub fn parse_name(attr: &Attribute) -> String {
-    parse_name_meta(&attr.meta)
-        .unwrap_or_else(|| panic!("Expected #[name = \"...\"] or #[name(\"...\")]."))
+    let name = parse_name_meta(&attr.meta)
+        .unwrap_or_else(|| panic!("Expected #[name = \"...\"] or #[name(\"...\")]."));
+    validate_identifier(&name);
+    name
 }
 
 pub fn parse_name_meta(meta: &Meta) -> Option<String> {
+    parse_str_meta(meta, "name")
+}
+
+/// Get the value as in `#[column("foo")]`
+pub fn parse_column(attr: &Attribute) -> String {
+    parse_str_meta(&attr.meta, "column")
+        .unwrap_or_else(|| panic!("Expected #[column = \"...\"] or #[column(\"...\")]."))
+}
+
+/// Get the string value of an attribute named `ident`, as in `#[ident("Foo")]` or `#[ident = "Foo"]`.
+pub fn parse_str_meta(meta: &Meta, ident: &str) -> Option<String> {
     match meta {
         // Parse #[name("Foo")].
         Meta::List(list) => {
-            if list.path.is_ident("name") {
+            if list.path.is_ident(ident) {
                 syn::parse2::<LitStr>(list.tokens.clone())
                     .map(|lit| lit.value())
                     .ok()
@@ -47,7 +64,7 @@ pub fn parse_name_meta(meta: &Meta) -> Option<String> {
         }
         // Parse #[name = "Foo"].
         Meta::NameValue(name_value) => {
-            if name_value.path.is_ident("name") {
+            if name_value.path.is_ident(ident) {
                 let expr = &name_value.value;
                 syn::parse2::<LitStr>(quote!(#expr))
                     .map(|lit| lit.value())
@@ -62,7 +79,10 @@ pub fn parse_name_meta(meta: &Meta) -> Option<String> {
 
 /// Get the labels as in `#[labels("Foo", "Bar")]`
 pub fn parse_labels(attr: &Attribute) -> Vec<String> {
-    parse_labels_meta(&attr.meta).unwrap_or_else(|| panic!("Expected #[labels(\"...\", \"...\")]."))
+    let labels = parse_labels_meta(&attr.meta)
+        .unwrap_or_else(|| panic!("Expected #[labels(\"...\", \"...\")]."));
+    labels.iter().for_each(|l| validate_identifier(l));
+    labels
 }
 
 pub fn parse_labels_meta(meta: &Meta) -> Option<Vec<String>> {
@@ -82,6 +102,101 @@ pub fn parse_labels_meta(meta: &Meta) -> Option<Vec<String>> {
     }
 }
 
+/// Panics if `name` can't be a label, relationship type, or property name, even in backticks.
+pub fn validate_identifier(name: &str) {
+    if name.is_empty() {
+        panic!("Names and labels cannot be empty.");
+    }
+    if name.contains('\0') {
+        panic!(
+            "Names and labels cannot contain a NUL character: {:?}",
+            name
+        );
+    }
+}
+
+/// Cypher's reserved words. Same as `cypher_dto::format::RESERVED`.
+const RESERVED: &[&str] = &[
+    "ADD",
+    "ALL",
+    "AND",
+    "AS",
+    "ASC",
+    "ASCENDING",
+    "BY",
+    "CALL",
+    "CASE",
+    "CONSTRAINT",
+    "CONTAINS",
+    "CREATE",
+    "DELETE",
+    "DESC",
+    "DESCENDING",
+    "DETACH",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "ELSE",
+    "END",
+    "ENDS",
+    "EXISTS",
+    "FALSE",
+    "FOR",
+    "IN",
+    "IS",
+    "LIMIT",
+    "MANDATORY",
+    "MATCH",
+    "MERGE",
+    "NOT",
+    "NULL",
+    "OF",
+    "ON",
+    "OPTIONAL",
+    "OR",
+    "ORDER",
+    "REMOVE",
+    "REQUIRE",
+    "RETURN",
+    "SCALAR",
+    "SET",
+    "SKIP",
+    "STARTS",
+    "THEN",
+    "TRUE",
+    "UNION",
+    "UNIQUE",
+    "UNWIND",
+    "WHEN",
+    "WHERE",
+    "WITH",
+    "XOR",
+    "YIELD",
+];
+
+/// Same as `cypher_dto::escape_identifier`, for the strings generated at compile time.
+pub fn escape_identifier(name: &str) -> String {
+    if is_plain(name) && !RESERVED.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+        name.to_owned()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+/// Same as `cypher_dto::format::format_placeholder`: `$name`, or `` $`zip-code` `` if it needs escaping.
+pub fn format_placeholder(name: &str) -> String {
+    if is_plain(name) {
+        format!("${}", name)
+    } else {
+        format!("$`{}`", name.replace('`', "``"))
+    }
+}
+
+fn is_plain(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
 struct Labels(Vec<String>);
 
 impl Parse for Labels {
@@ -112,4 +227,32 @@ mod tests {
         let attr: Attribute = parse_quote!(#[name = "Foo"]);
         assert_eq!(parse_name_meta(&attr.meta), Some("Foo".to_owned()));
     }
+
+    #[test]
+    fn test_parse_column() {
+        let attr: Attribute = parse_quote!(#[column("foo")]);
+        assert_eq!(parse_column(&attr), "foo");
+        let attr: Attribute = parse_quote!(#[column = "foo"]);
+        assert_eq!(parse_column(&attr), "foo");
+        let attr: Attribute = parse_quote!(#[name = "foo"]);
+        assert_eq!(parse_str_meta(&attr.meta, "column"), None);
+    }
+
+    #[test]
+    fn test_escape_identifier() {
+        assert_eq!(escape_identifier("zip_code"), "zip_code");
+        assert_eq!(escape_identifier("zip-code"), "`zip-code`");
+        assert_eq!(escape_identifier("order"), "`order`");
+        assert_eq!(format_placeholder("order"), "$order");
+        assert_eq!(format_placeholder("zip-code"), "$`zip-code`");
+        let attr: Attribute = parse_quote!(#[labels("My Label", "Foo")]);
+        assert_eq!(parse_labels(&attr), ["My Label", "Foo"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be empty")]
+    fn test_empty_name() {
+        let attr: Attribute = parse_quote!(#[name = ""]);
+        parse_name(&attr);
+    }
 }
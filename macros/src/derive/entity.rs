@@ -1,9 +1,11 @@
 mod builder;
 mod field;
+mod field_refs;
 mod fields;
 mod new_and_getters;
+mod patch;
 
-pub use field::{ArgHelper, EntityField, FieldType, StampType};
+pub use field::{bolt_value, ArgHelper, EntityField, FieldType, StampType};
 pub use fields::EntityFields;
 
 use crate::derive::{self, EntityType};
@@ -16,6 +18,7 @@ pub struct Entity {
     ident: Ident,
     name: String,
     labels: Vec<String>,
+    typ: EntityType,
     fields: EntityFields,
 }
 impl Entity {
@@ -38,6 +41,7 @@ impl Entity {
                 ident,
                 name: name.clone(),
                 labels: labels.clone(),
+                typ,
                 fields: fields.0,
             },
             Self {
@@ -45,6 +49,7 @@ impl Entity {
                 ident: id_ident,
                 name,
                 labels,
+                typ,
                 fields: fields.1,
             },
         )
@@ -69,11 +74,20 @@ impl Entity {
         builder::impl_builder(self)
     }
 
+    pub fn field_refs_impl(&self) -> TokenStream {
+        field_refs::impl_field_refs(self)
+    }
+
+    /// `id` is the id entity, and `id_trait` is `NodeId` or `RelationId`.
+    pub fn patch_impl(&self, id: &Entity, id_trait: TokenStream) -> TokenStream {
+        patch::impl_patch(self, id, id_trait)
+    }
+
     pub fn entity_impl(&self) -> TokenStream {
         let struct_ident = &self.ident;
         let struct_name = &self.name;
         let struct_labels = &self.labels;
-        let (idents, types, names, _comments, into_params, from_boltmaps) =
+        let (idents, types, names, _comments, into_params, into_maps, from_boltmaps) =
             self.fields.to_vectors();
         let types: Vec<&Type> = types.iter().map(|t| t.as_type()).collect();
 
@@ -81,10 +95,40 @@ impl Entity {
 
         let as_fields = names
             .iter()
-            .map(|n| format!("{n}: ${n}"))
+            .map(|n| {
+                format!(
+                    "{}: {}",
+                    derive::escape_identifier(n),
+                    derive::format_placeholder(n)
+                )
+            })
             .collect::<Vec<_>>()
             .join(", ");
-        let as_obj = format!("{} {{ {} }}", struct_labels.join(":"), as_fields);
+        let escaped_labels = struct_labels
+            .iter()
+            .map(|l| derive::escape_identifier(l))
+            .collect::<Vec<_>>();
+        let as_obj = format!("{} {{ {} }}", escaped_labels.join(":"), as_fields);
+        let projected = names
+            .iter()
+            .map(|n| format!(".{}", derive::escape_identifier(n)));
+
+        let from_rows_prefixed = self.fields.inner().iter().map(|f| f.from_row_prefixed());
+
+        // Nodes need all of the labels, and relationships the type, so other DTOs with the same fields aren't decoded.
+        let decode_graph_types = match self.typ {
+            EntityType::Node => quote! {
+                ::neo4rs::BoltType::Node(node)
+                    if [#(#struct_labels),*].iter().all(|label| node.labels.value.contains(&(*label).into())) =>
+                {
+                    node.properties.try_into()
+                }
+            },
+            EntityType::Relation => quote! {
+                ::neo4rs::BoltType::Relation(rel) if rel.typ.value == #struct_name => rel.properties.try_into(),
+                ::neo4rs::BoltType::UnboundedRelation(rel) if rel.typ.value == #struct_name => rel.properties.try_into(),
+            },
+        };
 
         quote! {
             impl ::cypher_dto::FieldSet for #struct_ident {
@@ -112,6 +156,12 @@ impl Entity {
                     #(query = #into_params;)*
                     query
                 }
+
+                fn to_bolt_map(&self, mode: ::cypher_dto::StampMode) -> ::neo4rs::BoltMap {
+                    let mut map = ::neo4rs::BoltMap::new();
+                    #(#into_maps;)*
+                    map
+                }
             }
             impl TryFrom<::neo4rs::Row> for #struct_ident {
                 type Error = ::cypher_dto::Error;
@@ -121,8 +171,56 @@ impl Entity {
                     })
                 }
             }
+            impl TryFrom<::neo4rs::BoltMap> for #struct_ident {
+                type Error = ::cypher_dto::Error;
+                fn try_from(value: ::neo4rs::BoltMap) -> ::std::result::Result<Self, Self::Error> {
+                    Ok(Self {
+                        #(#idents: #from_boltmaps),*
+                    })
+                }
+            }
+            impl TryFrom<::neo4rs::BoltType> for #struct_ident {
+                type Error = ::cypher_dto::Error;
+                /// Decodes a map, or the properties of a node with the same labels, or of a relationship with the same type.
+                fn try_from(value: ::neo4rs::BoltType) -> ::std::result::Result<Self, Self::Error> {
+                    match value {
+                        ::neo4rs::BoltType::Map(map) => map.try_into(),
+                        #decode_graph_types
+                        _ => Err(::cypher_dto::Error::TypeMismatch(#struct_name.to_owned())),
+                    }
+                }
+            }
+            impl ::cypher_dto::FromBolt for #struct_ident {
+                fn from_bolt(value: ::neo4rs::BoltType) -> ::std::result::Result<Self, ::cypher_dto::Error> {
+                    value.try_into()
+                }
+            }
+            impl ::cypher_dto::Projection for #struct_ident {
+                fn projection(var: &str) -> String {
+                    ::cypher_dto::format_projection(var, &[#(#projected),*] as &[&str])
+                }
+            }
             #new_and_getters
             impl #struct_ident {
+                /// Decodes a row with a column per field, named like its parameter with `prefix`
+                /// (see [format_param](::cypher_dto::format_param)), e.g. `p_name`.
+                ///
+                /// Use [Self::return_fields] to return the columns.
+                #[allow(unused_variables)]
+                pub fn try_from_row_prefixed(value: &::neo4rs::Row, prefix: Option<&str>) -> ::std::result::Result<Self, ::cypher_dto::Error> {
+                    let key = |name: &str| ::cypher_dto::format_param(name, prefix);
+                    Ok(Self {
+                        #(#idents: #from_rows_prefixed),*
+                    })
+                }
+
+                /// Formats the `RETURN` items for the fields of the node or relationship bound to `var`,
+                /// with the columns [Self::try_from_row_prefixed] reads, e.g. `p.name AS p_name`.
+                pub fn return_fields(var: &str, prefix: Option<&str>) -> String {
+                    ::cypher_dto::format_return_fields(&[#(#names),*] as &[&str], var, prefix)
+                }
+
+                #[allow(clippy::unused_unit)]
                 fn into_values(self) -> (#(#types),*) {
                     (#(self.#idents),*)
                 }
@@ -5,7 +5,8 @@ pub fn impl_builder(entity: &Entity) -> TokenStream {
     let entity_ident = entity.ident();
     let _entity_name = entity.name();
     let ident = format_ident!("{}Builder", entity_ident);
-    let (idents, types, names, comments, _into_params, _from_boltmaps) = entity.fields.to_vectors();
+    let (idents, types, names, comments, _into_params, _into_maps, _from_boltmaps) =
+        entity.fields.to_vectors();
 
     let mut all_types = Vec::new();
     let (
@@ -3,8 +3,9 @@ mod map_helper;
 mod query_helper;
 
 pub use field_type::{ArgHelper, FieldType};
+pub use query_helper::bolt_value;
 
-use quote::__private::TokenStream;
+use quote::{__private::TokenStream, quote};
 use syn::{Attribute, Field, Ident};
 
 use crate::derive;
@@ -25,7 +26,9 @@ pub struct EntityField {
     _is_skip: bool,
     comments: Vec<Attribute>,
     into_param: TokenStream,
+    into_map: TokenStream,
     from_boltmap: TokenStream,
+    from_row_prefixed: TokenStream,
     stamp_type: Option<StampType>,
 }
 impl EntityField {
@@ -58,7 +61,10 @@ impl EntityField {
             panic!("#[id] fields cannot be `Option`s (field: {})", ident);
         }
         let into_param = query_helper::add_value_to_params(&ident, &name, &typ, stamp.clone());
-        let from_boltmap = map_helper::field_from_boltmap(&name, &typ);
+        let into_map = query_helper::add_value_to_map(&ident, &name, &typ, stamp.clone());
+        let from_boltmap = map_helper::field_from_boltmap(quote!(#name), &typ);
+        // `key` is a closure that adds the prefix, in the generated `try_from_row_prefixed()`.
+        let from_row_prefixed = map_helper::field_from_boltmap(quote!(key(#name).as_str()), &typ);
         Self {
             ident,
             typ,
@@ -67,7 +73,9 @@ impl EntityField {
             _is_skip: is_skip,
             comments,
             into_param,
+            into_map,
             from_boltmap,
+            from_row_prefixed,
             stamp_type: stamp,
         }
     }
@@ -89,12 +97,22 @@ impl EntityField {
     pub fn comments(&self) -> &Vec<Attribute> {
         &self.comments
     }
+    #[allow(clippy::wrong_self_convention)]
     pub fn into_param(&self) -> &TokenStream {
         &self.into_param
     }
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_map(&self) -> &TokenStream {
+        &self.into_map
+    }
+    #[allow(clippy::wrong_self_convention)]
     pub fn from_boltmap(&self) -> &TokenStream {
         &self.from_boltmap
     }
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_row_prefixed(&self) -> &TokenStream {
+        &self.from_row_prefixed
+    }
     pub fn stamp_type(&self) -> &Option<StampType> {
         &self.stamp_type
     }
@@ -59,11 +59,19 @@ impl FieldType {
     }
 
     pub fn is_option(&self) -> bool {
-        match self {
-            FieldType::OptionDateTimeUtc(_) => true,
-            FieldType::OptionNum(_, _) => true,
-            FieldType::OptionOther(_) => true,
-            _ => false,
+        matches!(
+            self,
+            FieldType::OptionDateTimeUtc(_)
+                | FieldType::OptionNum(_, _)
+                | FieldType::OptionOther(_)
+        )
+    }
+
+    /// The `T` of an `Option<T>` field.
+    pub fn option_inner(&self) -> Option<&Type> {
+        match self.is_option() {
+            true => inner_type(self.as_type()),
+            false => None,
         }
     }
 }
@@ -135,6 +143,7 @@ impl ArgHelper {
             }
         }
     }
+    #[allow(clippy::type_complexity)]
     pub fn unzip(
         vec: Vec<ArgHelper>,
     ) -> (
@@ -220,11 +229,7 @@ fn is_copy_type(ty: &Type) -> bool {
     };
     let last_segment = path.path.segments.last().unwrap();
     let name = last_segment.ident.to_string();
-    match name.as_str() {
-        "bool" => true,
-        "char" => true,
-        _ => false,
-    }
+    matches!(name.as_str(), "bool" | "char")
 }
 
 /// Number info for adding to a [neo4rs::Query::param] or extracting from a BoltMap (e.g. [neo4rs::Row]).
@@ -310,12 +315,7 @@ impl Num {
     ///
     /// Based on cypher-dto/lib/src/entity.rs#L425
     pub fn map_uses_try_from(&self) -> bool {
-        match self {
-            Num::F32 => false,
-            Num::F64 => false,
-            Num::I64 => false,
-            _ => true,
-        }
+        !matches!(self, Num::F32 | Num::F64 | Num::I64)
     }
 
     /// Whether it uses `get::<X>` when converting from a [neo4rs::Row] or other BoltMap impl.
@@ -4,18 +4,19 @@ use quote::{__private::TokenStream, quote};
 /// The code for extracting a field from a [neo4rs::Row] or other BoltMap impl.
 ///
 /// It uses `value.get()` where value is a BoltMap such as a Row, Node, Relation, or UnboundedRelation.
-pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
+/// `key` is the `&str` expression for the key, usually the field name.
+pub fn field_from_boltmap(key: TokenStream, typ: &FieldType) -> TokenStream {
     match typ {
         FieldType::DateTimeUtc(_ty) => {
             quote!(
-                value.get::<::chrono::DateTime<::chrono::FixedOffset>>(#name)
+                value.get::<::chrono::DateTime<::chrono::FixedOffset>>(#key)
                     .map(|dt| dt.into())
-                    .map_err(|e| ::cypher_dto::Error::MissingField(#name.to_owned()))?
+                    .map_err(|e| ::cypher_dto::Error::MissingField(#key.to_owned()))?
             )
         }
         FieldType::OptionDateTimeUtc(_ty) => {
             quote!(
-                value.get::<::chrono::DateTime<::chrono::FixedOffset>>(#name)
+                value.get::<::chrono::DateTime<::chrono::FixedOffset>>(#key)
                     .map(|dt| dt.into()).ok()
             )
         }
@@ -23,14 +24,14 @@ pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
             // Build from the inside out.
             // Example: cypher-dto/lib/src/entity.rs#L425
             let mut tokens = quote!(
-                value.get(#name).map_err(|e| ::cypher_dto::Error::MissingField(#name.to_owned()))?
+                value.get(#key).map_err(|e| ::cypher_dto::Error::MissingField(#key.to_owned()))?
             );
 
             // Handle `value.get::<type>()`
             if let Some(type_arg) = num.map_getter_type_arg() {
                 let cast_type = type_arg.to_type();
                 tokens = quote!(
-                    value.get::<#cast_type>(#name).map_err(|e| ::cypher_dto::Error::MissingField(#name.to_owned()))?
+                    value.get::<#cast_type>(#key).map_err(|e| ::cypher_dto::Error::MissingField(#key.to_owned()))?
                 );
             }
 
@@ -39,7 +40,7 @@ pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
                 let try_from_type = num.to_type();
                 tokens = quote!(
                     #try_from_type::try_from(#tokens)
-                    .map_err(|_| ::cypher_dto::Error::TypeMismatch(#name.to_owned()))?
+                    .map_err(|_| ::cypher_dto::Error::TypeMismatch(#key.to_owned()))?
                 );
             }
 
@@ -82,7 +83,7 @@ pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
             //     None => None,
             // },
             let mut get_call = quote!(
-                value.get(#name)
+                value.get(#key)
             );
             let mut some_inner = quote!(v);
 
@@ -90,7 +91,7 @@ pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
             if let Some(type_arg) = num.map_getter_type_arg() {
                 let cast_type = type_arg.to_type();
                 get_call = quote!(
-                    value.get::<#cast_type>(#name)
+                    value.get::<#cast_type>(#key)
                 );
             }
 
@@ -99,7 +100,7 @@ pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
                 // let try_from_type = num.to_type();
                 some_inner = quote!(
                     v.try_into()
-                    .map_err(|_| ::cypher_dto::Error::TypeMismatch(#name.to_owned()))?
+                    .map_err(|_| ::cypher_dto::Error::TypeMismatch(#key.to_owned()))?
                 );
             }
 
@@ -118,12 +119,12 @@ pub fn field_from_boltmap(name: &str, typ: &FieldType) -> TokenStream {
         }
         FieldType::OptionOther(_ty) => {
             quote!(
-                value.get(#name).ok()
+                value.get(#key).ok()
             )
         }
         FieldType::Other(_ty) => {
             quote!(
-                value.get(#name).map_err(|e| ::cypher_dto::Error::MissingField(#name.to_owned()))?
+                value.get(#key).map_err(|e| ::cypher_dto::Error::MissingField(#key.to_owned()))?
             )
         }
     }
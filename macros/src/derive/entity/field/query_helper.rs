@@ -12,97 +12,74 @@ pub fn add_value_to_params(
     typ: &FieldType,
     stamp: Option<StampType>,
 ) -> TokenStream {
-    match typ {
-        FieldType::DateTimeUtc(_ty) => {
-            // Similar example: cypher-dto/lib/tests/common/entities/person.rs#L57
-            if stamp.is_some() {
-                match stamp.unwrap() {
-                    StampType::Created => {
-                        quote!(
-                            match mode {
-                                ::cypher_dto::StampMode::Create => query,
-                                _ => query.param(
-                                    &::cypher_dto::format_param(#name, prefix),
-                                    self.#ident.fixed_offset()
-                                )
-                            }
-                        )
-                    }
-                    StampType::Updated => {
-                        quote!(
-                            match mode {
-                                ::cypher_dto::StampMode::Read => query.param(
-                                    &::cypher_dto::format_param(#name, prefix),
-                                    self.#ident.fixed_offset()
-                                ),
-                                _ => query,
-                            }
-                        )
-                    }
-                }
-            } else {
-                quote!(
-                    query.param(
-                        &::cypher_dto::format_param(#name, prefix),
-                        self.#ident.fixed_offset()
-                    )
-                )
+    let value = bolt_value(quote!(self.#ident), typ);
+    with_stamp_mode(
+        quote!(
+            query.param(
+                &::cypher_dto::format_param(#name, prefix),
+                #value
+            )
+        ),
+        quote!(query),
+        stamp,
+    )
+}
+
+/// The code for adding a field to a [neo4rs::BoltMap], keyed by its name.
+///
+/// Uses a mutable [neo4rs::BoltMap] named `map`, and may put this field in it.
+/// The same fields are added as for [add_value_to_params].
+pub fn add_value_to_map(
+    ident: &Ident,
+    name: &str,
+    typ: &FieldType,
+    stamp: Option<StampType>,
+) -> TokenStream {
+    let value = bolt_value(quote!(self.#ident), typ);
+    with_stamp_mode(
+        quote!(map.put(#name.into(), (#value).into())),
+        quote!(()),
+        stamp,
+    )
+}
+
+/// Wraps `add` in a match on the [cypher_dto::StampMode] for timestamp fields,
+/// so it only runs in modes that use a placeholder for the field.
+///
+/// Similar example: cypher-dto/lib/tests/common/entities/person.rs#L57
+fn with_stamp_mode(add: TokenStream, skip: TokenStream, stamp: Option<StampType>) -> TokenStream {
+    match stamp {
+        Some(StampType::Created) => quote!(
+            match mode {
+                ::cypher_dto::StampMode::Create | ::cypher_dto::StampMode::Merge => #skip,
+                _ => #add,
             }
-        }
-        FieldType::OptionDateTimeUtc(_ty) => {
-            // Example: cypher-dto/lib/tests/common/entities/person.rs#L57
-            if stamp.is_some() {
-                match stamp.unwrap() {
-                    StampType::Created => {
-                        quote!(
-                            match mode {
-                                ::cypher_dto::StampMode::Create => query,
-                                _ => query.param(
-                                    &::cypher_dto::format_param(#name, prefix),
-                                    self.#ident.map(|v| v.fixed_offset())
-                                )
-                            }
-                        )
-                    }
-                    StampType::Updated => {
-                        quote!(
-                            match mode {
-                                ::cypher_dto::StampMode::Read => query.param(
-                                    &::cypher_dto::format_param(#name, prefix),
-                                    self.#ident.map(|v| v.fixed_offset())
-                                ),
-                                _ => query,
-                            }
-                        )
-                    }
-                }
-            } else {
-                quote!(
-                    query.param(
-                        &::cypher_dto::format_param(#name, prefix),
-                        self.#ident.map(|v| v.fixed_offset())
-                    )
-                )
+        ),
+        Some(StampType::Updated) => quote!(
+            match mode {
+                ::cypher_dto::StampMode::Read => #add,
+                _ => #skip,
             }
-        }
+        ),
+        None => add,
+    }
+}
+
+/// The value of a field, converted to something that is `Into<BoltType>`.
+///
+/// `value` is an expression for the field's value, e.g. `self.foo`. It's cloned if it isn't a number or datetime.
+pub fn bolt_value(value: TokenStream, typ: &FieldType) -> TokenStream {
+    match typ {
+        FieldType::DateTimeUtc(_ty) => quote!(#value.fixed_offset()),
+        FieldType::OptionDateTimeUtc(_ty) => quote!(#value.map(|v| v.fixed_offset())),
         FieldType::Num(_ty, num) => {
             // Non-optional numbers that need a cast use `as X`.
             if let Some(cast) = num.param_cast() {
                 // Example: cypher-dto/lib/src/entity.rs#L405
                 let ty = cast.to_type();
-                quote!(
-                    query.param(
-                        &::cypher_dto::format_param(#name, prefix),
-                        self.#ident as #ty
-                    )
-                )
+                quote!(#value as #ty)
             } else {
-                quote!(
-                    query.param(
-                        &::cypher_dto::format_param(#name, prefix),
-                        self.#ident
-                    )
-                )
+                quote!(#value)
             }
         }
         FieldType::OptionNum(_ty, num) => {
@@ -110,37 +87,12 @@ pub fn add_value_to_params(
             if let Some(cast) = num.param_cast() {
                 // Example: cypher-dto/lib/tests/common/entities/person.rs#L57
                 let ty = cast.to_type();
-                quote!(
-                    query.param(
-                        &::cypher_dto::format_param(#name, prefix),
-                        self.#ident.map(#ty::from)
-                    )
-                )
+                quote!(#value.map(#ty::from))
             } else {
-                quote!(
-                    query.param(
-                        &::cypher_dto::format_param(#name, prefix),
-                        self.#ident
-                    )
-                )
+                quote!(#value)
             }
         }
-        FieldType::OptionOther(_ty) => {
-            quote!(
-                query.param(
-                    &::cypher_dto::format_param(#name, prefix),
-                    self.#ident.clone()
-                )
-            )
-        }
-        FieldType::Other(_ty) => {
-            // Most fields are cloned.
-            quote!(
-                query.param(
-                    &::cypher_dto::format_param(#name, prefix),
-                    self.#ident.clone()
-                )
-            )
-        }
+        // Most fields are cloned.
+        FieldType::OptionOther(_ty) | FieldType::Other(_ty) => quote!(#value.clone()),
     }
 }
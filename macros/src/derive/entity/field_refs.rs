@@ -0,0 +1,47 @@
+use super::Entity;
+use quote::{__private::TokenStream, format_ident, quote};
+
+/// A struct with a [cypher_dto::Field] for each field, returned by `fn fields()`,
+/// and one with a [cypher_dto::FilterField] for each field, returned by `fn filter()`.
+pub fn impl_field_refs(entity: &Entity) -> TokenStream {
+    let entity_ident = entity.ident();
+    let entity_name = entity.name();
+    let vis = entity.vis();
+    let ident = format_ident!("{}Fields", entity_ident);
+    let filter_ident = format_ident!("{}Filter", entity_ident);
+    let (idents, types, names, _comments, _into_params, _into_maps, _from_boltmaps) =
+        entity.fields.to_vectors();
+    let types: Vec<_> = types.iter().map(|t| t.as_type()).collect();
+    let comment = format!(
+        "References to the fields of [`{}`], for use in typed queries.",
+        entity_ident
+    );
+    let filter_comment = format!("Builds a [`cypher_dto::Filter`] on [`{}`].", entity_ident);
+
+    quote! {
+        #[doc = #comment]
+        #[derive(Clone, Debug)]
+        #vis struct #ident {
+            #( pub #idents: ::cypher_dto::Field<#types>, )*
+        }
+        impl #entity_ident {
+            /// References to the fields of this struct, for use in typed queries.
+            pub fn fields() -> #ident {
+                #ident {
+                    #( #idents: ::cypher_dto::Field::new(#entity_name, #names), )*
+                }
+            }
+            /// Builds a filter on the fields of this struct, e.g. `Self::filter().field.eq(value)`.
+            pub fn filter() -> #filter_ident {
+                #filter_ident {
+                    #( #idents: ::cypher_dto::FilterField::new(#entity_name, #names), )*
+                }
+            }
+        }
+        #[doc = #filter_comment]
+        #[derive(Clone)]
+        #vis struct #filter_ident {
+            #( pub #idents: ::cypher_dto::FilterField<#entity_ident, #types>, )*
+        }
+    }
+}
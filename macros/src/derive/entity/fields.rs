@@ -33,6 +33,7 @@ impl EntityFields {
     }
 
     /// Helpful for using [quote::quote!].
+    #[allow(clippy::type_complexity)]
     pub fn to_vectors(
         &self,
     ) -> (
@@ -42,6 +43,7 @@ impl EntityFields {
         Vec<&Vec<Attribute>>,
         Vec<&TokenStream>,
         Vec<&TokenStream>,
+        Vec<&TokenStream>,
     ) {
         let len = self.inner.len();
         let mut idents = Vec::with_capacity(len);
@@ -49,6 +51,7 @@ impl EntityFields {
         let mut names = Vec::with_capacity(len);
         let mut comments = Vec::with_capacity(len);
         let mut into_params = Vec::with_capacity(len);
+        let mut into_maps = Vec::with_capacity(len);
         let mut from_boltmaps = Vec::with_capacity(len);
 
         for field in self.inner.iter() {
@@ -57,10 +60,19 @@ impl EntityFields {
             names.push(field.name());
             comments.push(field.comments());
             into_params.push(field.into_param());
+            into_maps.push(field.into_map());
             from_boltmaps.push(field.from_boltmap());
         }
 
-        (idents, types, names, comments, into_params, from_boltmaps)
+        (
+            idents,
+            types,
+            names,
+            comments,
+            into_params,
+            into_maps,
+            from_boltmaps,
+        )
     }
 }
 
@@ -95,7 +107,7 @@ fn parse_entity_fields(fields: Punctuated<Field, Comma>) -> Vec<EntityField> {
     entity_fields
 }
 
-fn find_id_fields(fields: &Vec<EntityField>, typ: EntityType) -> Vec<EntityField> {
+fn find_id_fields(fields: &[EntityField], typ: EntityType) -> Vec<EntityField> {
     let mut ids: Vec<EntityField> = Vec::new();
     let mut id_field: Option<&EntityField> = None;
 
@@ -113,7 +125,7 @@ fn find_id_fields(fields: &Vec<EntityField>, typ: EntityType) -> Vec<EntityField
             ids.push(field.clone());
         } else {
             ids = match typ {
-                EntityType::Node => fields.clone(),
+                EntityType::Node => fields.to_vec(),
                 EntityType::Relation => Vec::new(),
             };
         }
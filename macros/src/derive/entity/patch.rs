@@ -0,0 +1,100 @@
+use super::{bolt_value, Entity, FieldType};
+use quote::{__private::TokenStream, format_ident, quote};
+
+/// A struct with every field except the id fields and timestamps, each of which can be left out,
+/// and `apply()`, which updates only the fields that aren't.
+///
+/// `id_trait` is `NodeId` or `RelationId`, which does the update. `apply()` is left out if `id` has no fields,
+/// since a relation can't be matched without them.
+pub fn impl_patch(entity: &Entity, id: &Entity, id_trait: TokenStream) -> TokenStream {
+    let entity_ident = entity.ident();
+    let id_ident = id.ident();
+    let vis = entity.vis();
+    let ident = format_ident!("{}Patch", entity_ident);
+    let id_names: Vec<&str> = id.fields().inner().iter().map(|f| f.name()).collect();
+
+    let mut idents = Vec::new();
+    let mut types = Vec::new();
+    let mut comments = Vec::new();
+    let mut puts = Vec::new();
+    for field in entity.fields().inner() {
+        if id_names.contains(&field.name()) || field.stamp_type().is_some() {
+            continue;
+        }
+        let field_ident = field.ident();
+        let name = field.name();
+        match field.typ().option_inner() {
+            // `Option` fields can also be set to null.
+            Some(inner) => {
+                let value = bolt_value(quote!((*v)), &FieldType::parse(inner.clone()));
+                types.push(quote!(::cypher_dto::Patch<#inner>));
+                puts.push(quote!(
+                    match &self.#field_ident {
+                        ::cypher_dto::Patch::Keep => {}
+                        ::cypher_dto::Patch::Null => map.put(#name.into(), ::neo4rs::BoltType::Null(::neo4rs::BoltNull)),
+                        ::cypher_dto::Patch::Set(v) => map.put(#name.into(), (#value).into()),
+                    }
+                ));
+            }
+            None => {
+                let ty = field.typ().as_type();
+                let value = bolt_value(quote!((*v)), field.typ());
+                types.push(quote!(Option<#ty>));
+                puts.push(quote!(
+                    if let Some(v) = &self.#field_ident {
+                        map.put(#name.into(), (#value).into());
+                    }
+                ));
+            }
+        }
+        idents.push(field_ident);
+        comments.push(field.comments());
+    }
+    let (comment, apply) = if id_names.is_empty() {
+        (
+            format!(
+                "A partial update of [`{}`]: only the fields that are set are in `{}Patch::to_bolt_map()`.",
+                entity_ident, entity_ident
+            ),
+            quote!(),
+        )
+    } else {
+        (
+            format!(
+                "A partial update of [`{}`]: only the fields that are set are written, with `{}Patch::apply()`.",
+                entity_ident, entity_ident
+            ),
+            quote! {
+                /// Updates only the fields that are set, and the updated timestamp.
+                pub fn apply(&self, id: &#id_ident) -> ::neo4rs::Query {
+                    #id_trait::patch(id, self.to_bolt_map())
+                }
+                /// Like `apply()`, as a [`cypher_dto::CypherQuery`] whose text and parameters can be read.
+                pub fn apply_query(&self, id: &#id_ident) -> ::cypher_dto::CypherQuery {
+                    #id_trait::patch_query(id, self.to_bolt_map())
+                }
+            },
+        )
+    };
+
+    quote! {
+        #[doc = #comment]
+        #[derive(Default)]
+        #vis struct #ident {
+            #(
+                #( #comments )*
+                pub #idents: #types,
+            )*
+        }
+        impl #ident {
+            /// The fields that are set, keyed by their property names. [`cypher_dto::Patch::Null`] fields are null.
+            #[allow(unused_mut)]
+            pub fn to_bolt_map(&self) -> ::neo4rs::BoltMap {
+                let mut map = ::neo4rs::BoltMap::new();
+                #( #puts )*
+                map
+            }
+            #apply
+        }
+    }
+}
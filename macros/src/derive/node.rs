@@ -32,6 +32,10 @@ impl Node {
         let id_idents = self.id.fields().inner().iter().map(|f| f.ident());
         let entity_impl = self.inner.entity_impl();
         let builder_impl = self.inner.builder_impl();
+        let field_refs_impl = self.inner.field_refs_impl();
+        let patch_impl = self
+            .inner
+            .patch_impl(&self.id, quote!(::cypher_dto::NodeId));
         quote! {
             #entity_impl
             impl ::cypher_dto::NodeEntity for #main_ident {
@@ -51,6 +55,8 @@ impl Node {
                 }
             }
             #builder_impl
+            #field_refs_impl
+            #patch_impl
         }
     }
 
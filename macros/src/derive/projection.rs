@@ -0,0 +1,141 @@
+use crate::derive;
+use proc_macro::TokenStream;
+use quote::{__private::TokenStream as TokenStream2, quote};
+use syn::{Data, DeriveInput, Fields, GenericArgument, Ident, PathArguments, Type};
+
+/// A read-only struct decoded from a map projection, which may nest other projections.
+pub struct Projection {
+    ident: Ident,
+    fields: Vec<ProjectionField>,
+}
+
+struct ProjectionField {
+    ident: Ident,
+    /// The key in the projected map, and the property name for non-nested fields.
+    name: String,
+    /// The variable and type of a `#[nested]` field.
+    nested: Option<Nested>,
+}
+
+struct Nested {
+    var: String,
+    /// The DTO type, e.g. `Person` for `Vec<Person>`.
+    inner: Type,
+    /// Whether the field is a `Vec` that is filled by `collect()`.
+    is_list: bool,
+}
+
+impl Projection {
+    pub fn new(input: DeriveInput) -> Self {
+        let fields = match input.data {
+            Data::Struct(data_struct) => match data_struct.fields {
+                Fields::Named(fields_named) => fields_named.named,
+                _ => panic!("Projection requires a struct with named fields"),
+            },
+            _ => panic!("Only structs are supported"),
+        };
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap().clone();
+                let mut name = ident.to_string();
+                let mut nested = None;
+                for attr in field.attrs.iter() {
+                    if attr.path().is_ident("name") {
+                        name = derive::parse_name(attr);
+                    } else if attr.path().is_ident("nested") {
+                        // `#[nested]` uses the field name as the variable.
+                        let var = derive::parse_str_meta(&attr.meta, "nested")
+                            .unwrap_or_else(|| ident.to_string());
+                        let (inner, is_list) = unwrap_container(&field.ty);
+                        nested = Some(Nested {
+                            var,
+                            inner,
+                            is_list,
+                        });
+                    }
+                }
+                ProjectionField {
+                    ident,
+                    name,
+                    nested,
+                }
+            })
+            .collect();
+        Self {
+            ident: input.ident,
+            fields,
+        }
+    }
+
+    pub fn to_token_stream(&self) -> TokenStream {
+        let ident = &self.ident;
+        let type_name = ident.to_string();
+        let idents = self.fields.iter().map(|f| &f.ident);
+        let names = self.fields.iter().map(|f| &f.name);
+        let items = self.fields.iter().map(|f| f.projection_item());
+        quote! {
+            impl ::cypher_dto::Projection for #ident {
+                fn projection(var: &str) -> String {
+                    ::cypher_dto::format_projection(var, [#(#items),*])
+                }
+            }
+            impl ::cypher_dto::FromBolt for #ident {
+                fn from_bolt(value: ::neo4rs::BoltType) -> ::std::result::Result<Self, ::cypher_dto::Error> {
+                    let mut map = match value {
+                        ::neo4rs::BoltType::Map(map) => map,
+                        _ => return Err(::cypher_dto::Error::TypeMismatch(#type_name.to_owned())),
+                    };
+                    Ok(Self {
+                        #(#idents: ::cypher_dto::FromBolt::from_map_key(&mut map, #names)?),*
+                    })
+                }
+            }
+        }
+        .into()
+    }
+}
+
+impl ProjectionField {
+    /// The code for this field's part of the projection, e.g. `.name` or `friends: collect(f{.*})`.
+    fn projection_item(&self) -> TokenStream2 {
+        let name = derive::escape_identifier(&self.name);
+        match &self.nested {
+            None => {
+                let item = format!(".{}", name);
+                quote!(#item.to_owned())
+            }
+            Some(Nested {
+                var,
+                inner,
+                is_list,
+            }) => {
+                let format = if *is_list {
+                    "{}: collect({})"
+                } else {
+                    "{}: {}"
+                };
+                quote!(
+                    format!(#format, #name, <#inner as ::cypher_dto::Projection>::projection(#var))
+                )
+            }
+        }
+    }
+}
+
+/// Returns `T` and whether it was a list, for a field of type `T`, `Option<T>`, or `Vec<T>`.
+fn unwrap_container(ty: &Type) -> (Type, bool) {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            let is_list = segment.ident == "Vec";
+            if is_list || segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return (inner.clone(), is_list);
+                    }
+                }
+            }
+        }
+    }
+    (ty.clone(), false)
+}
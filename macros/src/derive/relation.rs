@@ -44,6 +44,10 @@ impl Relation {
         let id_idents = self.id.fields().inner().iter().map(|f| f.ident());
         let entity_impl = self.inner.entity_impl();
         let builder_impl = self.inner.builder_impl();
+        let field_refs_impl = self.inner.field_refs_impl();
+        let patch_impl = self
+            .inner
+            .patch_impl(&self.id, quote!(::cypher_dto::RelationId));
         quote! {
             #entity_impl
             impl ::cypher_dto::RelationEntity for #main_ident {
@@ -71,6 +75,8 @@ impl Relation {
                 }
             }
             #builder_impl
+            #field_refs_impl
+            #patch_impl
         }
     }
 
@@ -0,0 +1,65 @@
+use crate::derive;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields, Generics, Ident};
+
+/// A struct of query results, where each field is one column of a [neo4rs::Row].
+pub struct CypherRow {
+    ident: Ident,
+    generics: Generics,
+    idents: Vec<Ident>,
+    columns: Vec<String>,
+}
+impl CypherRow {
+    pub fn new(input: DeriveInput) -> Self {
+        let fields = match input.data {
+            Data::Struct(data_struct) => match data_struct.fields {
+                Fields::Named(fields_named) => fields_named.named,
+                _ => panic!("CypherRow requires a struct with named fields"),
+            },
+            _ => panic!("Only structs are supported"),
+        };
+        let mut idents = Vec::new();
+        let mut columns = Vec::new();
+        for field in fields.iter() {
+            let ident = field.ident.as_ref().unwrap().clone();
+            let mut column = ident.to_string();
+            for attr in field.attrs.iter() {
+                if attr.path().is_ident("column") {
+                    column = derive::parse_column(attr);
+                }
+            }
+            idents.push(ident);
+            columns.push(column);
+        }
+        // Every type parameter has to be decodable for the columns that use it to be.
+        let mut generics = input.generics;
+        for param in generics.type_params_mut() {
+            param.bounds.push(parse_quote!(::cypher_dto::FromBolt));
+        }
+        Self {
+            ident: input.ident,
+            generics,
+            idents,
+            columns,
+        }
+    }
+
+    pub fn to_token_stream(&self) -> TokenStream {
+        let ident = &self.ident;
+        let idents = &self.idents;
+        let columns = &self.columns;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        quote! {
+            impl #impl_generics TryFrom<::neo4rs::Row> for #ident #ty_generics #where_clause {
+                type Error = ::cypher_dto::Error;
+                fn try_from(value: ::neo4rs::Row) -> ::std::result::Result<Self, Self::Error> {
+                    Ok(Self {
+                        #(#idents: ::cypher_dto::FromBolt::from_column(&value, #columns)?),*
+                    })
+                }
+            }
+        }
+        .into()
+    }
+}
@@ -3,7 +3,7 @@ use proc_macro::TokenStream;
 mod derive;
 mod timestamps;
 
-use derive::{Node, Relation};
+use derive::{CypherRow, Node, Projection, Relation};
 use syn::{parse_macro_input, DeriveInput};
 
 /// Derives the [NodeEntity](::cypher_dto::NodeEntity) and related traits.
@@ -20,6 +20,29 @@ pub fn derive_relation(input: TokenStream) -> TokenStream {
     Relation::new(input).to_token_stream()
 }
 
+/// Implements `TryFrom<neo4rs::Row>` for a struct of query results, with one field per returned column.
+///
+/// Columns are matched by field name, or by `#[column = "..."]`. Each field is decoded with
+/// [FromBolt](::cypher_dto::FromBolt), so it can be a `Node` or `Relation` DTO, a `Vec` of
+/// values from `collect()`, or an `Option` for columns that may be null or missing.
+#[proc_macro_derive(CypherRow, attributes(column))]
+pub fn derive_cypher_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    CypherRow::new(input).to_token_stream()
+}
+
+/// Implements [Projection](::cypher_dto::Projection) and [FromBolt](::cypher_dto::FromBolt) for a read-only struct
+/// that is returned as a map projection.
+///
+/// Fields are projected as properties (`.name`), unless they are marked `#[nested]` or `#[nested = "var"]`.
+/// Nested fields use the projection of another DTO bound to `var` (the field name by default).
+/// A `Vec` is filled with `collect()`, and an `Option` is `None` if the variable is null, e.g. from an `OPTIONAL MATCH`.
+#[proc_macro_derive(Projection, attributes(name, nested))]
+pub fn derive_projection(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    Projection::new(input).to_token_stream()
+}
+
 /// Adds created/updated timestamp fields to a struct, using [`Option<DateTime<Utc>>`] as the type.
 ///
 /// The default field names are `created_at` and `updated_at`, but can be changed